@@ -1,14 +1,32 @@
-use soroban_sdk::{Address, String, contracttype};
+use soroban_sdk::{Address, BytesN, String, contracttype};
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Config {
     pub name: String,
     pub symbol: String,
+    pub home_domain: String,
     pub admin: Address,
     pub storage_address: Address,
     pub transfer_fee_percent: u32,
     pub burn_fee_percent: u32,
     pub platform_fee_percent: u32,
     pub storage_fee_percent: u32,
+    pub acceptance_timeout_secs: u64,
+    pub capacity_warning_bps: u32,
+    pub guarantee_required_above: i128, // Futures up to this amount may omit the agent
+    pub unguaranteed_share_to_buyer: bool, // Else the agent share goes to the platform
+    pub max_total_supply: i128,         // 0 = unlimited, raised only via timelock
+    pub attestation_max_age_secs: u64,  // Auto-pause series with older attestations, 0 = off
+    pub insurance_reserve_bps: u32,     // Slice of each mint held in the insurance buffer
+    pub oracle_max_age_secs: u64,       // Swaps reject oracle prices older than this
+    pub delivery_fee_bps: u32,          // Facility operator's cut of each confirmed delivery
+    pub high_fee_split_allowed: bool,   // Mints may route over MAX_FEE_SPLIT_BPS to fees
+    pub default_grace_secs: u64,        // Past delivery_date + this, an undelivered future expires
+    pub default_to_agent: bool,         // Defaulted locked tokens go to the agent, else the buyer
+    pub ttl_threshold: u32,             // Holder and series entries closer to archival get extended
+    pub ttl_extend_to: u32,             // Ledgers of life an extended entry is given
+    // Code set by the last upgrade. None until the first one: a contract can't read the hash
+    // it was deployed with, so the deploy-time code is never recorded here.
+    pub installed_wasm: Option<BytesN<32>>,
 }
@@ -0,0 +1,86 @@
+use super::{ContangoToken, ContangoTokenArgs, ContangoTokenClient, DataKey};
+use soroban_sdk::{Address, Env, Map, String, Symbol, contractimpl, contracttype};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeriesAllowlist {
+    pub producer_managed: bool, // Producer instead of admin manages the list
+    pub recipients: Map<Address, bool>,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Choose whether the producer or the admin manages a series recipient allowlist (admin only)
+    pub fn set_series_allowlist_manager(env: Env, series_id: String, producer_managed: bool) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+        Self::require_series(&env, &series_id);
+
+        let mut allowlist = Self::get_series_allowlist(&env, &series_id);
+        allowlist.producer_managed = producer_managed;
+        Self::store(&env).set(&DataKey::SeriesAllowlist(series_id), &allowlist);
+    }
+
+    /// Allow an address to receive tokens of a series, closing the series to everyone else
+    pub fn add_series_recipient(env: Env, series_id: String, recipient: Address) {
+        Self::ensure_initialized(&env);
+        let mut allowlist = Self::authorize_allowlist_manager(&env, &series_id);
+        allowlist.recipients.set(recipient.clone(), true);
+        Self::store(&env).set(&DataKey::SeriesAllowlist(series_id.clone()), &allowlist);
+
+        env.events().publish(
+            (Symbol::new(&env, "series_recipient_added"), series_id),
+            recipient,
+        );
+    }
+
+    /// Remove an address from a series recipient allowlist
+    pub fn remove_series_recipient(env: Env, series_id: String, recipient: Address) {
+        Self::ensure_initialized(&env);
+        let mut allowlist = Self::authorize_allowlist_manager(&env, &series_id);
+        allowlist.recipients.remove(recipient.clone());
+        Self::store(&env).set(&DataKey::SeriesAllowlist(series_id.clone()), &allowlist);
+
+        env.events().publish(
+            (Symbol::new(&env, "series_recipient_removed"), series_id),
+            recipient,
+        );
+    }
+
+    /// Check whether an address may receive tokens of a series
+    pub fn is_series_recipient(env: Env, series_id: String, recipient: Address) -> bool {
+        Self::is_allowed_recipient(&env, &series_id, &recipient)
+    }
+}
+
+impl ContangoToken {
+    pub(super) fn get_series_allowlist(env: &Env, series_id: &String) -> SeriesAllowlist {
+        Self::store(env)
+            .get(&DataKey::SeriesAllowlist(series_id.clone()))
+            .unwrap_or(SeriesAllowlist {
+                producer_managed: false,
+                recipients: Map::new(env),
+            })
+    }
+
+    pub(super) fn authorize_allowlist_manager(env: &Env, series_id: &String) -> SeriesAllowlist {
+        let metadata = Self::require_series(env, series_id);
+        let allowlist = Self::get_series_allowlist(env, series_id);
+
+        if allowlist.producer_managed {
+            metadata.producer.require_auth();
+        } else {
+            Self::load_config(env).admin.require_auth();
+        }
+        allowlist
+    }
+
+    pub(super) fn is_allowed_recipient(env: &Env, series_id: &String, recipient: &Address) -> bool {
+        // Series without an allowlist stay open to everyone
+        match Self::store(env).get::<SeriesAllowlist>(&DataKey::SeriesAllowlist(series_id.clone()))
+        {
+            Some(allowlist) => allowlist.recipients.contains_key(recipient.clone()),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, Distribution, Role,
+    SeriesMetadata, checked_add,
+};
+use soroban_sdk::{
+    Address, Env, Map, String, Symbol, Vec, contractimpl, contracttype, log, panic_with_error,
+};
+
+/// Upper bound on legs in a single netting
+pub const MAX_NETTING_LEGS: u32 = 20;
+
+/// Upper bound on items in a single delivery batch
+pub const MAX_DELIVERY_BATCH: u32 = 20;
+
+/// Upper bound on recipients in a single batch transfer
+pub const MAX_TRANSFER_BATCH: u32 = 20;
+
+/// Upper bound on entries in a single batch mint; each mint writes a dozen entries
+pub const MAX_MINT_BATCH: u32 = 10;
+
+/// One series of a `mint_spot_batch`, with the arguments `mint_spot` takes
+#[contracttype]
+#[derive(Clone)]
+pub struct MintEntry {
+    pub series_id: String,
+    pub metadata: SeriesMetadata,
+    pub distribution: Distribution,
+    pub amount: i128,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Mint several spot series under one auth (Minter role); any failing entry reverts all.
+    /// Entries are validated as in `mint_spot` and a series may appear only once.
+    pub fn mint_spot_batch(env: Env, minter: Address, entries: Vec<MintEntry>) {
+        Self::require_not_paused(&env);
+        Self::require_minting_enabled(&env);
+        let config = Self::load_config(&env);
+        Self::require_role(&env, Role::Minter, &minter);
+
+        if entries.len() > MAX_MINT_BATCH {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+
+        let mut seen: Map<String, bool> = Map::new(&env);
+        for entry in entries.iter() {
+            if seen.contains_key(entry.series_id.clone()) {
+                panic_with_error!(env, ContractError::DuplicateItem);
+            }
+            seen.set(entry.series_id.clone(), true);
+            Self::require_non_negative(&env, entry.amount);
+            Self::do_mint_spot(
+                &env,
+                &config,
+                &minter,
+                entry.series_id,
+                entry.metadata,
+                entry.distribution,
+                entry.amount,
+            );
+        }
+    }
+
+    /// Confirm receipts for several series under one validator auth; any failing item reverts all
+    ///
+    /// Each item releases that many locked tokens. An item covering the buyer's whole
+    /// remaining locked balance completes the series, a smaller one is a partial delivery.
+    /// Items run in input order; a series may appear only once.
+    pub fn confirm_delivery_batch(
+        env: Env,
+        storage_validator: Address,
+        items: Vec<(String, i128)>,
+    ) {
+        Self::require_not_busy(&env);
+        Self::load_config(&env);
+        storage_validator.require_auth();
+
+        if items.len() > MAX_DELIVERY_BATCH {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+
+        let mut seen: Map<String, bool> = Map::new(&env);
+        for (series_id, amount) in items.iter() {
+            if amount <= 0 {
+                panic_with_error!(env, ContractError::InvalidAmount);
+            }
+            if seen.contains_key(series_id.clone()) {
+                panic_with_error!(env, ContractError::DuplicateItem);
+            }
+            seen.set(series_id.clone(), true);
+            let metadata = match Self::delivery_gate(&env, &series_id, &storage_validator) {
+                Ok(m) => m,
+                Err(reason) => {
+                    // Contract events roll back with the trap; the diagnostic log survives
+                    log!(
+                        &env,
+                        "confirm_delivery_batch rejected series",
+                        series_id,
+                        reason.get_code()
+                    );
+                    panic_with_error!(env, reason);
+                }
+            };
+            Self::record_delivery(&env, &storage_validator, &series_id, &metadata, amount);
+        }
+    }
+
+    /// Pay several recipients from one balance; any failing entry reverts all.
+    /// The total is checked up front and each leg pays the transfer fee policy's fee.
+    /// `apply_fee` is deprecated and ignored, pass None.
+    pub fn transfer_batch(
+        env: Env,
+        from: Address,
+        recipients: Vec<(Address, i128)>,
+        apply_fee: Option<bool>,
+    ) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        let config = Self::load_config(&env);
+        from.require_auth();
+
+        if recipients.len() > MAX_TRANSFER_BATCH {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+        Self::note_apply_fee_deprecated(&env, &from, apply_fee);
+        let mut total: i128 = 0;
+        for (_, amount) in recipients.iter() {
+            if amount <= 0 {
+                panic_with_error!(env, ContractError::InvalidAmount);
+            }
+            total = checked_add(&env, total, amount);
+        }
+        if Self::get_balance(&env, &from) < total {
+            panic_with_error!(env, ContractError::InsufficientBalance);
+        }
+
+        for (to, amount) in recipients.iter() {
+            let fee = Self::transfer_fee(&env, &config, &from, &to, amount);
+            Self::do_transfer(&env, &config, &from, &to, amount, fee, None);
+        }
+    }
+
+    /// Net offsetting per-series obligations between two parties; positive legs flow a to b.
+    /// Legs for the same series are summed, and series settle in order of first appearance.
+    pub fn net_settle(env: Env, party_a: Address, party_b: Address, legs: Vec<(String, i128)>) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        Self::ensure_initialized(&env);
+        party_a.require_auth();
+        party_b.require_auth();
+
+        if legs.len() > MAX_NETTING_LEGS {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+
+        let config = Self::load_config(&env);
+        let mut nets: Map<String, i128> = Map::new(&env);
+        let mut order: Vec<String> = Vec::new(&env);
+        for (series_id, amount) in legs.iter() {
+            Self::require_series(&env, &series_id);
+            Self::require_fresh_backing(&env, &series_id);
+            let net = match nets.get(series_id.clone()) {
+                Some(net) => net,
+                None => {
+                    order.push_back(series_id.clone());
+                    0
+                }
+            };
+            nets.set(series_id, checked_add(&env, net, amount));
+        }
+
+        // Fees apply only to what actually moves per series
+        for series_id in order.iter() {
+            let net = nets.get(series_id.clone()).unwrap_or(0);
+            let (from, to) = if net > 0 {
+                (&party_a, &party_b)
+            } else {
+                (&party_b, &party_a)
+            };
+            if net != 0 {
+                let amount = net.abs();
+                let fee = Self::transfer_fee(&env, &config, from, to, amount);
+                Self::do_transfer(&env, &config, from, to, amount, fee, Some(&series_id));
+            }
+            env.events().publish(
+                (
+                    Symbol::new(&env, "net_settled"),
+                    party_a.clone(),
+                    party_b.clone(),
+                ),
+                (series_id, net),
+            );
+        }
+    }
+
+    /// Exchange tokens of two series between two holders in one step (both parties): either
+    /// both legs settle or neither does, each paying the transfer fee policy's fee.
+    /// Returns the exchange id, the op log sequence shared by both legs' events.
+    /// `apply_fee` is deprecated and ignored, pass None.
+    pub fn atomic_exchange(
+        env: Env,
+        party_a: Address,
+        series_a: String,
+        amount_a: i128,
+        party_b: Address,
+        series_b: String,
+        amount_b: i128,
+        apply_fee: Option<bool>,
+    ) -> u64 {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        let config = Self::load_config(&env);
+        if amount_a <= 0 || amount_b <= 0 {
+            panic_with_error!(env, ContractError::InvalidAmount);
+        }
+        party_a.require_auth();
+        party_b.require_auth();
+        for series_id in [&series_a, &series_b] {
+            Self::require_series(&env, series_id);
+            Self::require_fresh_backing(&env, series_id);
+        }
+
+        Self::note_apply_fee_deprecated(&env, &party_a, apply_fee);
+        let exchange_id = Self::log_op(&env, "exchange", &party_a, None, amount_a);
+        for (from, to, series_id, amount) in [
+            (&party_a, &party_b, &series_a, amount_a),
+            (&party_b, &party_a, &series_b, amount_b),
+        ] {
+            let fee = Self::transfer_fee(&env, &config, from, to, amount);
+            Self::do_transfer(&env, &config, from, to, amount, fee, Some(series_id));
+            env.events().publish(
+                (Symbol::new(&env, "exchange_leg"), exchange_id),
+                (from.clone(), to.clone(), series_id.clone(), amount, fee),
+            );
+        }
+        exchange_id
+    }
+}
@@ -0,0 +1,59 @@
+use super::{
+    AgentEscrow, ContangoToken, ContangoTokenArgs, ContangoTokenClient, DataKey, TokenState,
+};
+use crate::config::Config;
+use soroban_sdk::{Env, Map, String, contractimpl, contracttype};
+
+#[cfg(not(debug_assertions))]
+compile_error!("the debug-views feature must not be enabled in release builds");
+
+/// Raw storage counters, read without going through the public views
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugCounters {
+    pub total_supply: i128,
+    pub total_locked: i128,   // Sum of the per-series locked counters
+    pub total_escrowed: i128, // Sum of the guarantee fees held in agent escrow
+    pub insurance_buffer: i128,
+    pub admin_balance: i128,   // Where platform fees accrue
+    pub storage_balance: i128, // Where default storage fees accrue
+    pub series_outstanding: Map<String, i128>,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Dump the raw accounting counters (debug builds only)
+    pub fn debug_counters(env: Env) -> DebugCounters {
+        let storage = Self::store(&env);
+        let read = |key: DataKey| -> i128 { storage.get(&key).unwrap_or(0) };
+
+        let config: Config = storage.get(&DataKey::Config).unwrap();
+        let state: Option<TokenState> = storage.get(&DataKey::State);
+        let mut counters = DebugCounters {
+            total_supply: state.map_or(0, |state| state.total_supply),
+            total_locked: 0,
+            total_escrowed: 0,
+            insurance_buffer: read(DataKey::InsuranceBuffer),
+            admin_balance: read(DataKey::Balance(config.admin)),
+            storage_balance: read(DataKey::Balance(config.storage_address)),
+            series_outstanding: Map::new(&env),
+        };
+
+        let count: u32 = storage.get(&DataKey::SeriesCount).unwrap_or(0);
+        for index in 0..count {
+            let Some(series_id) = storage.get::<String>(&DataKey::SeriesByIndex(index)) else {
+                continue;
+            };
+            counters.total_locked += read(DataKey::SeriesLocked(series_id.clone()));
+            if let Some(escrow) =
+                storage.get::<AgentEscrow>(&DataKey::AgentEscrow(series_id.clone()))
+            {
+                counters.total_escrowed += escrow.amount;
+            }
+            counters
+                .series_outstanding
+                .set(series_id.clone(), read(DataKey::SeriesSupply(series_id)));
+        }
+        counters
+    }
+}
@@ -0,0 +1,288 @@
+use super::{
+    AgentEscrow, BurnKind, ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError,
+    DataKey, DeliveryEvent, ExtKey, Reason, ReasonCode, SeriesMetadata, SeriesStatus,
+    SettingChange, checked_add, checked_sub,
+};
+use soroban_sdk::{Address, Env, String, Symbol, contractimpl, panic_with_error};
+
+#[contractimpl]
+impl ContangoToken {
+    /// Hand a future's guarantee to another agent, escrowed fee included
+    /// (admin, current agent and new agent, before delivery)
+    pub fn replace_guarantee_agent(env: Env, series_id: String, new_agent: Address) {
+        let config = Self::load_config(&env);
+        let mut metadata = Self::require_deliverable(&env, &series_id);
+        let old_agent = Self::require_guarantee_agent(&env, &metadata);
+        if new_agent == old_agent {
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+        config.admin.require_auth();
+        old_agent.require_auth();
+        new_agent.require_auth();
+
+        metadata.guarantee_agent = Some(new_agent.clone());
+        Self::store_series(&env, &series_id, &metadata);
+        Self::stamp_series(&env, &series_id, &metadata);
+
+        let key = DataKey::AgentEscrow(series_id.clone());
+        let mut moved = 0;
+        if let Some(mut escrow) = Self::store(&env).get::<AgentEscrow>(&key) {
+            let old_pending = Self::agent_pending_earnings(env.clone(), old_agent.clone());
+            Self::store(&env).set(
+                &DataKey::AgentPending(old_agent.clone()),
+                &checked_sub(&env, old_pending, escrow.amount),
+            );
+            let new_pending = Self::agent_pending_earnings(env.clone(), new_agent.clone());
+            Self::store(&env).set(
+                &DataKey::AgentPending(new_agent.clone()),
+                &checked_add(&env, new_pending, escrow.amount),
+            );
+            escrow.agent = new_agent.clone();
+            Self::store(&env).set(&key, &escrow);
+            moved = escrow.amount;
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "guarantee_agent_replaced"), series_id),
+            (old_agent, new_agent, moved),
+        );
+    }
+
+    /// Settle a failed future: the guarantee fee goes to the buyer instead of the agent (admin only)
+    pub fn settle_default(env: Env, series_id: String) {
+        Self::require_not_busy(&env);
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let metadata = Self::require_deliverable(&env, &series_id);
+        let compensation = Self::mark_defaulted(&env, &series_id, &metadata);
+
+        env.events().publish(
+            (Symbol::new(&env, "default_settled"), series_id),
+            compensation,
+        );
+    }
+
+    /// Default a future whose delivery date plus the grace period has passed (guarantee agent
+    /// or admin). The buyer's locked tokens are released to the buyer, or to the agent under
+    /// `default_to_agent`; the guarantee fee goes to the buyer as in `settle_default`.
+    pub fn declare_default(env: Env, caller: Address, series_id: String) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        let config = Self::load_config(&env);
+        caller.require_auth();
+
+        let metadata = Self::require_deliverable(&env, &series_id);
+        if caller != config.admin && metadata.guarantee_agent.as_ref() != Some(&caller) {
+            panic_with_error!(env, ContractError::Unauthorized);
+        }
+        let deadline = metadata
+            .delivery_date
+            .saturating_add(config.default_grace_secs);
+        if env.ledger().timestamp() <= deadline {
+            panic_with_error!(env, ContractError::TimelockNotExpired);
+        }
+
+        let buyer = Self::require_buyer(&env, &metadata);
+        let recipient = match &metadata.guarantee_agent {
+            Some(agent) if config.default_to_agent => agent.clone(),
+            _ => buyer.clone(),
+        };
+        let released = Self::locked_in_series(&env, &series_id, &buyer);
+        if released > 0 {
+            Self::decrease_locked_balance(&env, &buyer, &series_id, released);
+            Self::increase_balance(&env, &recipient, &series_id, released);
+        }
+        let compensation = Self::mark_defaulted(&env, &series_id, &metadata);
+
+        env.events().publish(
+            (Symbol::new(&env, "default_declared"), series_id),
+            (recipient, released, compensation),
+        );
+    }
+
+    /// Cancel a future before delivery by mutual agreement (buyer, plus the admin or the
+    /// guarantee agent). The buyer's locked tokens are burned and the facility's kg freed;
+    /// fees paid at mint stay put and the escrowed guarantee fee goes to the agent.
+    pub fn cancel_future(env: Env, approver: Address, series_id: String) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        let config = Self::load_config(&env);
+
+        let metadata = Self::require_deliverable(&env, &series_id);
+        let buyer = Self::require_buyer(&env, &metadata);
+        buyer.require_auth();
+        approver.require_auth();
+        if approver != config.admin && metadata.guarantee_agent.as_ref() != Some(&approver) {
+            panic_with_error!(env, ContractError::Unauthorized);
+        }
+
+        let amount = Self::locked_in_series(&env, &series_id, &buyer);
+        if amount > 0 {
+            Self::decrease_locked_balance(&env, &buyer, &series_id, amount);
+            let mut state = Self::get_state(&env);
+            state.total_supply = checked_sub(&env, state.total_supply, amount);
+            Self::put_state(&env, &state);
+            Self::adjust_series_supply(&env, &series_id, -amount);
+            Self::record_burn(&env, BurnKind::Cancellation, amount);
+        }
+        Self::release_agent_escrow(&env, &series_id, None);
+        Self::release_facility_kg(&env, &metadata.storage_facility, metadata.quantity_kg);
+        Self::store(&env).set(
+            &DataKey::SeriesStatus(series_id.clone()),
+            &SeriesStatus::Cancelled,
+        );
+
+        Self::log_op(
+            &env,
+            "cancel_future",
+            &buyer,
+            Some(series_id.clone()),
+            amount,
+        );
+        env.events().publish(
+            (Symbol::new(&env, "future_cancelled"), series_id.clone()),
+            (buyer.clone(), amount),
+        );
+        Self::release_payment_escrow(&env, &series_id, &buyer);
+    }
+
+    /// Contest a future's delivery while its tokens are still locked (buyer only).
+    /// Deliveries, defaults and cancellation wait until the guarantee agent resolves it.
+    /// The reason is kept until then; `note` is for `ReasonCode::Other` only.
+    pub fn raise_dispute(env: Env, series_id: String, reason: ReasonCode, note: Option<String>) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        Self::ensure_initialized(&env);
+        let metadata = Self::require_deliverable(&env, &series_id);
+        let buyer = Self::require_buyer(&env, &metadata);
+        Self::require_guarantee_agent(&env, &metadata);
+        Self::require_buyer_consent(&env, &buyer, "raise_dispute", &series_id);
+
+        let locked = Self::locked_in_series(&env, &series_id, &buyer);
+        if locked == 0 {
+            panic_with_error!(env, ContractError::NoLockedTokens);
+        }
+        let reason = Self::make_reason(&env, reason, note);
+        Self::store(&env).set(
+            &DataKey::SeriesStatus(series_id.clone()),
+            &SeriesStatus::Disputed,
+        );
+        Self::store(&env).set(&ExtKey::DisputeReason(series_id.clone()), &reason);
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_raised"), series_id),
+            (locked, reason),
+        );
+    }
+
+    /// Why a series' delivery is disputed, while the dispute is open
+    pub fn dispute_reason(env: Env, series_id: String) -> Option<Reason> {
+        Self::store(&env).get(&ExtKey::DisputeReason(series_id))
+    }
+
+    /// Settle a dispute over `amount` of the locked tokens (guarantee agent only): delivered to
+    /// the buyer as by `confirm_delivery`, or else returned to the producer. Rulings add up;
+    /// once nothing is left locked the series completes like a delivery, with any escrowed
+    /// payment split between producer and buyer in proportion to what was released and
+    /// returned. Otherwise the series awaits delivery again.
+    pub fn resolve_dispute(env: Env, series_id: String, release_to_buyer: bool, amount: i128) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        Self::ensure_initialized(&env);
+        let metadata = Self::require_series(&env, &series_id);
+        if Self::get_series_status(&env, &series_id, &metadata) != SeriesStatus::Disputed {
+            panic_with_error!(env, ContractError::NothingPending);
+        }
+        let agent = Self::require_guarantee_agent(&env, &metadata);
+        agent.require_auth();
+
+        let buyer = Self::require_buyer(&env, &metadata);
+        let locked = Self::locked_in_series(&env, &series_id, &buyer);
+        if amount <= 0 {
+            panic_with_error!(env, ContractError::InvalidAmount);
+        }
+        if amount > locked {
+            panic_with_error!(env, ContractError::InsufficientBalance);
+        }
+
+        // Back to awaiting delivery, unless this ruling settles the rest
+        Self::store(&env).remove(&DataKey::SeriesStatus(series_id.clone()));
+        Self::store(&env).remove(&ExtKey::DisputeReason(series_id.clone()));
+        let recipient = if release_to_buyer {
+            let recipient = Self::resolve_token_recipient(&env, &series_id, &buyer);
+            Self::record_delivery(&env, &agent, &series_id, &metadata, amount);
+            recipient
+        } else {
+            Self::decrease_locked_balance(&env, &buyer, &series_id, amount);
+            Self::increase_balance(&env, &metadata.producer, &series_id, amount);
+            if amount == locked {
+                let delivery = DeliveryEvent {
+                    amount,
+                    validator: agent,
+                    facility: metadata.storage_facility.clone(),
+                    fee: 0,
+                };
+                Self::complete_delivery(&env, &series_id, &metadata, delivery, 0, amount);
+            } else {
+                Self::note_settled(&env, &series_id, 0, amount);
+            }
+            metadata.producer.clone()
+        };
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_resolved"), series_id),
+            (release_to_buyer, recipient, amount),
+        );
+    }
+
+    /// Set the future size above which a guarantee agent is mandatory (admin only)
+    pub fn set_guarantee_policy(
+        env: Env,
+        required_above: i128,
+        share_to_buyer: bool,
+    ) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        config.guarantee_required_above = required_above;
+        config.unguaranteed_share_to_buyer = share_to_buyer;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, required_above)
+    }
+
+    /// Set the grace period after a future's delivery date before it can be declared defaulted,
+    /// and whether its locked tokens then go to the guarantee agent (admin only)
+    pub fn set_default_policy(env: Env, grace_secs: u64, to_agent: bool) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        config.default_grace_secs = grace_secs;
+        config.default_to_agent = to_agent;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, "default_policy_changed"),),
+            (grace_secs, to_agent),
+        );
+        Self::setting_applied(&env, grace_secs as i128)
+    }
+}
+
+impl ContangoToken {
+    /// Mark a future defaulted, pay its escrowed guarantee fee to the buyer, refund its
+    /// payment and count the default against the producer; returns the compensation paid
+    pub(super) fn mark_defaulted(env: &Env, series_id: &String, metadata: &SeriesMetadata) -> i128 {
+        Self::store(env).set(
+            &DataKey::SeriesStatus(series_id.clone()),
+            &SeriesStatus::Defaulted,
+        );
+        let buyer = Self::require_buyer(env, metadata);
+        let compensation = Self::release_agent_escrow(env, series_id, Some(buyer.clone()));
+
+        Self::record_producer_default(env, &metadata.producer);
+        Self::release_payment_escrow(env, series_id, &buyer);
+        compensation
+    }
+}
@@ -0,0 +1,194 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, ExtKey,
+    SeriesMetadata, checked_add, checked_sub,
+};
+use soroban_sdk::{
+    Address, Env, String, Symbol, contractimpl, contracttype, panic_with_error, token,
+};
+
+/// A future's purchase price, held by the contract in a payment token (e.g. a USDC SAC)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentEscrow {
+    pub token: Address,
+    pub amount: i128, // Paid to the producer on delivery, refunded on default or cancellation
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Mint a future like `mint_future` and pull the buyer's payment of `price` in
+    /// `payment_token` into escrow (Minter role and buyer)
+    pub fn open_future_with_escrow(
+        env: Env,
+        minter: Address,
+        series_id: String,
+        metadata: SeriesMetadata,
+        buyer: Address,
+        token_recipient: Option<Address>,
+        guarantee_agent: Option<Address>,
+        amount: i128,
+        payment_token: Address,
+        price: i128,
+    ) {
+        Self::require_not_busy(&env);
+        if price <= 0 {
+            panic_with_error!(env, ContractError::InvalidAmount);
+        }
+        let key = DataKey::PaymentEscrow(series_id.clone());
+        if Self::store(&env).has(&key) {
+            panic_with_error!(env, ContractError::AlreadyPending);
+        }
+        buyer.require_auth();
+
+        Self::mint_future(
+            env.clone(),
+            minter,
+            series_id.clone(),
+            metadata,
+            buyer.clone(),
+            token_recipient,
+            guarantee_agent,
+            amount,
+        );
+        let escrow = PaymentEscrow {
+            token: payment_token.clone(),
+            amount: price,
+        };
+        Self::store(&env).set(&key, &escrow);
+        env.events().publish(
+            (Symbol::new(&env, "payment_escrowed"), series_id),
+            (buyer.clone(), payment_token.clone(), price),
+        );
+
+        Self::call_out(&env, || {
+            token::Client::new(&env, &payment_token).transfer(
+                &buyer,
+                &env.current_contract_address(),
+                &price,
+            )
+        });
+    }
+
+    /// Get the payment held for a future until delivery
+    pub fn payment_escrow(env: Env, series_id: String) -> Option<PaymentEscrow> {
+        Self::store(&env).get(&DataKey::PaymentEscrow(series_id))
+    }
+
+    /// Send a future's escrowed payment to another address at delivery, e.g. a bank's
+    /// collection account (producer and admin, before delivery); the producer clears it
+    pub fn set_payout_route(env: Env, series_id: String, recipient: Address) {
+        let config = Self::load_config(&env);
+        let metadata = Self::require_deliverable(&env, &series_id);
+        metadata.producer.require_auth();
+        config.admin.require_auth();
+
+        let key = ExtKey::PayoutRoute(series_id.clone());
+        let old = Self::resolve_payout_recipient(&env, &series_id, &metadata.producer);
+        if recipient == metadata.producer {
+            Self::store(&env).remove(&key);
+        } else {
+            Self::store(&env).set(&key, &recipient);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "payout_route_changed"), series_id),
+            (old, recipient),
+        );
+    }
+
+    /// Get the address a future's escrowed payment is routed to, if not the producer
+    pub fn payout_route(env: Env, series_id: String) -> Option<Address> {
+        Self::store(&env).get(&ExtKey::PayoutRoute(series_id))
+    }
+}
+
+impl ContangoToken {
+    /// Where a future's payment goes at delivery: its payout route, else the producer
+    pub(super) fn resolve_payout_recipient(
+        env: &Env,
+        series_id: &String,
+        producer: &Address,
+    ) -> Address {
+        Self::store(env)
+            .get(&ExtKey::PayoutRoute(series_id.clone()))
+            .unwrap_or_else(|| producer.clone())
+    }
+
+    /// Pay out a series' escrowed payment, if any, and forget it; returns the amount paid.
+    /// Makes an external call, so callers run it after their own state writes
+    pub(super) fn release_payment_escrow(
+        env: &Env,
+        series_id: &String,
+        recipient: &Address,
+    ) -> i128 {
+        let key = DataKey::PaymentEscrow(series_id.clone());
+        let escrow: PaymentEscrow = match Self::store(env).get(&key) {
+            Some(e) => e,
+            None => return 0,
+        };
+        Self::store(env).remove(&key);
+
+        env.events().publish(
+            (Symbol::new(env, "payment_released"), series_id.clone()),
+            (recipient.clone(), escrow.amount),
+        );
+        Self::call_out(env, || {
+            token::Client::new(env, &escrow.token).transfer(
+                &env.current_contract_address(),
+                recipient,
+                &escrow.amount,
+            )
+        });
+        escrow.amount
+    }
+
+    /// Pay out a completed future's escrowed payment, if any: the producer's payee is paid for
+    /// the `released` tokens and the buyer refunded for the `returned` ones. Returns the amount
+    /// refunded. Makes external calls, so callers run it after their own state writes
+    pub(super) fn settle_payment_escrow(
+        env: &Env,
+        series_id: &String,
+        payee: &Address,
+        buyer: &Address,
+        released: i128,
+        returned: i128,
+    ) -> i128 {
+        if returned == 0 {
+            Self::release_payment_escrow(env, series_id, payee);
+            return 0;
+        }
+
+        let key = DataKey::PaymentEscrow(series_id.clone());
+        let escrow: PaymentEscrow = match Self::store(env).get(&key) {
+            Some(e) => e,
+            None => return 0,
+        };
+        Self::store(env).remove(&key);
+        let refund = escrow
+            .amount
+            .checked_mul(returned)
+            .map(|scaled| scaled / checked_add(env, released, returned))
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::ArithmeticOverflow));
+        let paid = checked_sub(env, escrow.amount, refund);
+
+        env.events().publish(
+            (Symbol::new(env, "payment_released"), series_id.clone()),
+            (payee.clone(), paid),
+        );
+        env.events().publish(
+            (Symbol::new(env, "payment_refunded"), series_id.clone()),
+            (buyer.clone(), refund),
+        );
+        Self::call_out(env, || {
+            let client = token::Client::new(env, &escrow.token);
+            let contract = env.current_contract_address();
+            if paid > 0 {
+                client.transfer(&contract, payee, &paid);
+            }
+            if refund > 0 {
+                client.transfer(&contract, buyer, &refund);
+            }
+        });
+        refund
+    }
+}
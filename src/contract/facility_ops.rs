@@ -0,0 +1,231 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, FeeShare,
+    SeriesMetadata, SettingChange, checked_bps,
+};
+use soroban_sdk::{
+    Address, Env, Map, String, Symbol, Vec, contractimpl, contracttype, panic_with_error, vec,
+};
+
+/// Cap on the delivery confirmation fee
+pub const MAX_DELIVERY_FEE_BPS: u32 = 25;
+
+/// Upper bound on storage fee recipients per facility
+pub const MAX_FEE_RECIPIENTS: u32 = 4;
+
+/// Weekdays and UTC hours in which a facility confirms deliveries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfirmationWindow {
+    pub days_mask: u32,  // Bit 0 = Monday .. bit 6 = Sunday
+    pub start_hour: u32, // Inclusive
+    pub end_hour: u32,   // Exclusive, at most 24
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Deactivate a facility so it takes on no new kg, or reactivate it (admin only)
+    pub fn set_facility_active(env: Env, facility: String, active: bool) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let key = DataKey::FacilityInactive(facility.clone());
+        if active {
+            Self::store(&env).remove(&key);
+        } else {
+            let record = Self::get_facility_record(&env, &facility);
+            Self::store_facility(&env, &facility, &record);
+            Self::store(&env).set(&key, &true);
+        }
+
+        env.events()
+            .publish((Symbol::new(&env, "facility_active"), facility), active);
+    }
+
+    /// Restrict delivery confirmations at a facility to a weekly window, None to lift it (admin only)
+    pub fn set_confirmation_window(env: Env, facility: String, window: Option<ConfirmationWindow>) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if let Some(window) = &window {
+            if window.days_mask == 0 || window.days_mask > 0x7f {
+                panic_with_error!(env, ContractError::InvalidParameter);
+            }
+            if window.start_hour >= window.end_hour || window.end_hour > 24 {
+                panic_with_error!(env, ContractError::InvalidParameter);
+            }
+        }
+
+        let key = DataKey::ConfirmationWindow(facility.clone());
+        match &window {
+            Some(window) => Self::store(&env).set(&key, window),
+            None => Self::store(&env).remove(&key),
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "confirmation_window_set"), facility),
+            window,
+        );
+    }
+
+    /// Get a facility's confirmation window, None when it confirms at any time
+    pub fn confirmation_window(env: Env, facility: String) -> Option<ConfirmationWindow> {
+        Self::store(&env).get(&DataKey::ConfirmationWindow(facility))
+    }
+
+    /// Let a facility confirm outside its window during an emergency (admin only)
+    pub fn set_confirmation_override(env: Env, facility: String, enabled: bool) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let mut record = Self::get_facility_record(&env, &facility);
+        record.window_override = enabled;
+        Self::store_facility(&env, &facility, &record);
+
+        env.events().publish(
+            (Symbol::new(&env, "confirmation_override"), facility),
+            enabled,
+        );
+    }
+
+    /// Set the fee paid to a facility operator out of each confirmed delivery (admin only)
+    pub fn set_delivery_fee(env: Env, fee_bps: u32) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if fee_bps > MAX_DELIVERY_FEE_BPS {
+            panic_with_error!(env, ContractError::FeeTooHigh);
+        }
+
+        config.delivery_fee_bps = fee_bps;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, fee_bps as i128)
+    }
+
+    /// Route a facility's storage-side fees to weighted recipients, empty to reset (admin only).
+    /// Shares are paid in input order; a recipient may appear only once.
+    pub fn set_facility_fee_recipients(env: Env, facility: String, recipients: Vec<FeeShare>) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if recipients.len() > MAX_FEE_RECIPIENTS {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+        let mut seen: Map<Address, bool> = Map::new(&env);
+        for share in recipients.iter() {
+            if seen.contains_key(share.recipient.clone()) {
+                panic_with_error!(env, ContractError::DuplicateItem);
+            }
+            seen.set(share.recipient, true);
+        }
+        let total_bps: u64 = recipients.iter().map(|share| share.bps as u64).sum();
+        if !recipients.is_empty() && total_bps != 10000 {
+            panic_with_error!(env, ContractError::InvalidDistribution);
+        }
+
+        let mut record = Self::get_facility_record(&env, &facility);
+        record.fee_recipients = recipients;
+        Self::store_facility(&env, &facility, &record);
+    }
+
+    /// Set the facility utilization above which a capacity warning is emitted (admin only)
+    pub fn set_capacity_warning_threshold(env: Env, threshold_bps: u32) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if threshold_bps > 10000 {
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+
+        config.capacity_warning_bps = threshold_bps;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, threshold_bps as i128)
+    }
+}
+
+impl ContangoToken {
+    /// Whether the facility's confirmation window, if any, is open right now
+    pub(super) fn confirmation_window_status(
+        env: &Env,
+        facility: &String,
+    ) -> Result<(), ContractError> {
+        let window = match Self::confirmation_window(env.clone(), facility.clone()) {
+            Some(window) if !Self::get_facility_record(env, facility).window_override => window,
+            _ => return Ok(()),
+        };
+
+        let now = env.ledger().timestamp();
+        let hour = (now % 86400 / 3600) as u32;
+        if window.days_mask & (1 << weekday(now)) == 0
+            || hour < window.start_hour
+            || hour >= window.end_hour
+        {
+            return Err(ContractError::OutsideConfirmationWindow);
+        }
+        Ok(())
+    }
+
+    /// The facility operator's cut of a delivery; none while the facility
+    /// runs under an emergency confirmation override
+    pub(super) fn delivery_fee(
+        env: &Env,
+        metadata: &SeriesMetadata,
+        amount: i128,
+    ) -> Option<(Address, i128)> {
+        let fee_bps = Self::load_config(env).delivery_fee_bps;
+        let facility = Self::get_facility_record(env, &metadata.storage_facility);
+        match facility.operator {
+            Some(operator) if fee_bps > 0 && !facility.window_override => {
+                Some((operator, checked_bps(env, amount, fee_bps as i128)))
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn pay_delivery_fee(
+        env: &Env,
+        series_id: &String,
+        metadata: &SeriesMetadata,
+        buyer: &Address,
+        amount: i128,
+    ) -> i128 {
+        let Some((operator, fee)) = Self::delivery_fee(env, metadata, amount) else {
+            return 0;
+        };
+        Self::increase_balance(env, &operator, series_id, fee);
+        Self::record_fee_paid(env, buyer, fee);
+        fee
+    }
+
+    /// Split a storage-side fee across the facility's recipients, remainder to the last one
+    pub(super) fn storage_fee_split(
+        env: &Env,
+        facility: &String,
+        default_recipient: &Address,
+        amount: i128,
+    ) -> Vec<(Address, i128)> {
+        let recipients = Self::get_facility_record(env, facility).fee_recipients;
+        if recipients.is_empty() {
+            return vec![env, (default_recipient.clone(), amount)];
+        }
+
+        let mut split = Vec::new(env);
+        let mut remaining = amount;
+        for (i, share) in recipients.iter().enumerate() {
+            let credit = if i as u32 == recipients.len() - 1 {
+                remaining
+            } else {
+                checked_bps(env, amount, share.bps as i128)
+            };
+            remaining -= credit;
+            split.push_back((share.recipient, credit));
+        }
+        split
+    }
+}
+
+/// Day of the week of `timestamp`, 0 = Monday (the Unix epoch fell on a Thursday)
+fn weekday(timestamp: u64) -> u32 {
+    ((timestamp / 86400 + 3) % 7) as u32
+}
@@ -0,0 +1,542 @@
+#[cfg(feature = "recovery")]
+use super::recovery::AssistedBurn;
+use super::{
+    AgentEscrow, BurnKind, ContangoToken, ContangoTokenArgs, ContangoTokenClient, DataKey, ExtKey,
+    Facility, PendingDelivery, Reason, SeriesMetadata, SeriesStamps, checked_add,
+};
+use crate::storage_types::{AllowanceDataKey, AllowanceValue};
+use soroban_sdk::{
+    Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec, contractimpl, contracttype,
+};
+
+/// Number of recent operations kept for support lookups
+pub const OP_LOG_SIZE: u64 = 200;
+
+/// Operations returned per `recent_ops` page
+pub const OP_PAGE_SIZE: u64 = 20;
+
+/// Most series IDs returned by one `get_series_ids` call
+pub const MAX_SERIES_PAGE: u32 = 100;
+
+/// Most series positions, and approvals of each kind, in one `statement_of` page
+pub const MAX_STATEMENT_PAGE: u32 = 20;
+
+/// Most facilities returned by one `list_facilities` page
+pub const MAX_FACILITY_PAGE: u32 = 100;
+
+/// Entry of the recent operations ring buffer, a convenience cache and not an archive
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpRecord {
+    pub seq: u64,
+    pub op_kind: Symbol,
+    pub actor: Address,
+    pub series: Option<String>,
+    pub amount: i128,
+    pub ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AddressStatement {
+    pub owner: Address,
+    pub balance: i128,
+    pub locked_balance: i128,
+    pub fees_paid: i128, // Cumulative transfer and burn fees paid
+    pub frozen: bool,
+    pub series_count: u32,                 // Series positions across all pages
+    pub series: Vec<SeriesPosition>,       // This page's positions, spendable holdings first
+    pub approvals_granted: Vec<Approval>,  // This page of spenders the owner approved
+    pub approvals_received: Vec<Approval>, // This page of owners who approved the address
+}
+
+/// One series in an address statement
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeriesPosition {
+    pub series_id: String,
+    pub free: i128,
+    pub locked: i128,
+    pub awaiting_acceptance: i128, // Delivered, held until the buyer accepts
+    pub pending_burn: i128,        // Scheduled assisted burn against the holding
+}
+
+/// An allowance as seen from one side; `amount` is zero once spent or expired
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approval {
+    pub counterparty: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenMetadataView {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub home_domain: String, // Issuer home domain, e.g. "contango.com.br"
+    pub series_count: u32,
+}
+
+/// A registered facility as shown to admin tooling
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FacilityView {
+    pub name: String,
+    pub operator: Option<Address>,
+    pub active: bool, // Inactive facilities take on no new kg
+    pub capacity_kg: u64,
+    pub backed_kg: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeriesInfo {
+    pub metadata: SeriesMetadata,
+    pub stamps: SeriesStamps,
+    pub supply: i128,  // Tokens outstanding against the series
+    pub locked: i128,  // Part of the supply still locked until delivery
+    pub expired: bool, // Past delivery date plus grace, undelivered; see `is_expired`
+}
+
+/// Entry points with a published footprint hint
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OpKind {
+    MintSpot,
+    MintFuture,
+    Transfer,
+    TransferSeries,
+    Burn,
+    BurnSeries,
+    Swap,
+    ConfirmDelivery,
+}
+
+/// Conservative resource bounds for one call, for pre-sizing transactions
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FootprintHint {
+    pub reads: u32,  // Read-only ledger entries, including Wasm code
+    pub writes: u32, // Written ledger entries, including auth nonces
+    pub instructions_estimate: u64,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Get token metadata in the shape wallets expect
+    pub fn token_metadata(env: Env) -> Option<TokenMetadataView> {
+        let metadata = Self::stored_token_metadata(&env)?;
+        let config = Self::load_config(&env);
+
+        Some(TokenMetadataView {
+            name: metadata.name,
+            symbol: metadata.symbol,
+            decimals: metadata.decimal,
+            home_domain: config.home_domain,
+            series_count: Self::series_count(&env),
+        })
+    }
+
+    /// Set the issuer home domain advertised to wallets (admin only)
+    pub fn set_home_domain(env: Env, domain: String) {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        config.home_domain = domain;
+        Self::store(&env).set(&DataKey::Config, &config);
+    }
+
+    /// Resource bounds for an entry point, with headroom for Wasm execution. Entry counts
+    /// cover the holder and series entries touched, including ones probed but absent;
+    /// `test_footprint_hints` fails when a change outgrows them.
+    pub fn footprint_hint(_env: Env, op_kind: OpKind) -> FootprintHint {
+        let (reads, writes, instructions_estimate) = match op_kind {
+            OpKind::MintSpot => (20, 20, 10_000_000),
+            OpKind::MintFuture => (20, 20, 15_000_000),
+            // A charged fee may call the rewards hook contract
+            OpKind::Transfer | OpKind::TransferSeries => (12, 10, 15_000_000),
+            OpKind::Burn | OpKind::BurnSeries => (12, 14, 15_000_000),
+            // Reads the oracle's instance and code
+            OpKind::Swap => (14, 14, 20_000_000),
+            OpKind::ConfirmDelivery => (20, 20, 20_000_000),
+        };
+        FootprintHint {
+            reads,
+            writes,
+            instructions_estimate,
+        }
+    }
+
+    /// Why the contract is paused, if it is
+    pub fn pause_reason(env: Env) -> Option<Reason> {
+        Self::stored_reason(&env, &DataKey::Paused)
+    }
+
+    /// Why an account is frozen, if it is
+    pub fn freeze_reason(env: Env, addr: Address) -> Option<Reason> {
+        Self::stored_reason(&env, &DataKey::Frozen(addr))
+    }
+
+    /// Get the tokens an owner still has locked in one future series
+    pub fn locked_balance_of_series(env: Env, owner: Address, series_id: String) -> i128 {
+        match Self::load_series(&env, &series_id) {
+            Some(metadata) if metadata.buyer.as_ref() == Some(&owner) => {
+                Self::locked_in_series(&env, &series_id, &owner)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Get the number of series created so far
+    pub fn get_series_count(env: Env) -> u32 {
+        Self::series_count(&env)
+    }
+
+    /// Get the guarantee fee held for a series until delivery
+    pub fn agent_escrow(env: Env, series_id: String) -> Option<AgentEscrow> {
+        Self::store(&env).get(&DataKey::AgentEscrow(series_id))
+    }
+
+    /// Get a delivery awaiting buyer acceptance
+    pub fn pending_delivery(env: Env, series_id: String) -> Option<PendingDelivery> {
+        Self::store(&env).get(&DataKey::PendingDelivery(series_id))
+    }
+
+    /// Get the late delivery penalty accrued so far for a future series
+    pub fn accrued_penalty(env: Env, series_id: String) -> i128 {
+        let metadata = match Self::load_series(&env, &series_id) {
+            Some(m) => m,
+            None => return 0,
+        };
+
+        match metadata.buyer.clone() {
+            Some(buyer) if metadata.is_future => {
+                let locked = Self::locked_in_series(&env, &series_id, &buyer);
+                Self::compute_late_penalty(&env, &metadata, locked)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Get the capacity and backed kg of a storage facility
+    pub fn get_facility(env: Env, facility: String) -> Option<Facility> {
+        Self::store(&env).get(&DataKey::Facility(facility))
+    }
+
+    /// Get the series an address holds, in the order they were acquired
+    pub fn held_series(env: Env, owner: Address) -> Vec<String> {
+        Self::get_held_series(&env, &owner)
+    }
+
+    /// Get series metadata together with its stamps, supply and locked tokens
+    pub fn series_info(env: Env, series_id: String) -> Option<SeriesInfo> {
+        let metadata = Self::load_series(&env, &series_id)?;
+        Some(SeriesInfo {
+            stamps: Self::get_series_stamps(&env, &series_id),
+            supply: Self::series_supply(env.clone(), series_id.clone()),
+            locked: Self::series_locked(&env, &series_id),
+            expired: Self::series_expired(&env, &series_id, &metadata),
+            metadata,
+        })
+    }
+
+    /// List series IDs in creation order, at most `MAX_SERIES_PAGE` from `start`
+    pub fn get_series_ids(env: Env, start: u32, limit: u32) -> Vec<String> {
+        let end = Self::series_count(&env).min(start.saturating_add(limit.min(MAX_SERIES_PAGE)));
+        let mut ids = Vec::new(&env);
+        let mut legacy: Option<Vec<String>> = None;
+        for index in start..end {
+            let id = Self::store(&env)
+                .get(&DataKey::SeriesByIndex(index))
+                // Series from before the index are numbered in legacy map order
+                .or_else(|| {
+                    legacy
+                        .get_or_insert_with(|| {
+                            Self::legacy_state(&env)
+                                .map_or_else(|| Vec::new(&env), |legacy| legacy.series.keys())
+                        })
+                        .get(index)
+                });
+            if let Some(id) = id {
+                ids.push_back(id);
+            }
+        }
+        ids
+    }
+
+    /// Get the tokens burned so far, split by why they were burned
+    pub fn burn_breakdown(env: Env) -> Map<BurnKind, i128> {
+        Self::store(&env)
+            .get(&DataKey::BurnBreakdown)
+            .unwrap_or_else(|| Map::new(&env))
+    }
+
+    /// Get a page of recent operations, newest first
+    pub fn recent_ops(env: Env, page: u32) -> Vec<OpRecord> {
+        let latest: u64 = Self::store(&env).get(&DataKey::OpSeq).unwrap_or(0);
+        let oldest = latest.saturating_sub(OP_LOG_SIZE) + 1;
+
+        let mut ops = Vec::new(&env);
+        let start = latest.saturating_sub(page as u64 * OP_PAGE_SIZE);
+        let mut seq = start;
+        while seq >= oldest && seq > 0 && start - seq < OP_PAGE_SIZE {
+            if let Some(op) = Self::op_by_seq(env.clone(), seq) {
+                ops.push_back(op);
+            }
+            seq -= 1;
+        }
+        ops
+    }
+
+    /// Look up an operation by sequence number, None once evicted from the buffer
+    pub fn op_by_seq(env: Env, seq: u64) -> Option<OpRecord> {
+        Self::store(&env)
+            .get::<OpRecord>(&DataKey::OpLog(seq % OP_LOG_SIZE))
+            .filter(|op| op.seq == seq)
+    }
+
+    /// Get a page of an address's position statement: totals and status on every page, then
+    /// the page's slice of its series positions and of the approvals it granted and received
+    pub fn statement_of(env: Env, owner: Address, page: u32, page_size: u32) -> AddressStatement {
+        let page_size = page_size.min(MAX_STATEMENT_PAGE);
+        let start = page.saturating_mul(page_size);
+
+        // Spendable holdings, then series where the tokens are only locked or awaiting acceptance
+        let mut positions = Self::get_held_series(&env, &owner);
+        let locked_series: Vec<String> = Self::store(&env)
+            .get(&ExtKey::LockedSeries(owner.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        for series_id in locked_series.iter() {
+            if !positions.contains(&series_id) {
+                positions.push_back(series_id);
+            }
+        }
+        let mut series = Vec::new(&env);
+        for series_id in Self::page_of(&positions, start, page_size).iter() {
+            let locked = if locked_series.contains(&series_id) {
+                Self::series_locked(&env, &series_id)
+            } else {
+                0
+            };
+            let awaiting_acceptance = Self::store(&env)
+                .get::<PendingDelivery>(&DataKey::PendingDelivery(series_id.clone()))
+                .filter(|pending| pending.buyer == owner)
+                .map_or(0, |pending| pending.amount);
+            #[cfg(feature = "recovery")]
+            let pending_burn = Self::store(&env)
+                .get::<AssistedBurn>(&DataKey::AssistedBurn(series_id.clone(), owner.clone()))
+                .map_or(0, |burn| burn.amount);
+            #[cfg(not(feature = "recovery"))]
+            let pending_burn = 0;
+            series.push_back(SeriesPosition {
+                free: Self::get_series_balance(&env, &owner, &series_id),
+                series_id,
+                locked,
+                awaiting_acceptance,
+                pending_burn,
+            });
+        }
+
+        let mut approvals_granted = Vec::new(&env);
+        let spenders: Vec<Address> = Self::store(&env)
+            .get(&ExtKey::Spenders(owner.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        for spender in Self::page_of(&spenders, start, page_size).iter() {
+            approvals_granted.push_back(Self::approval(&env, &owner, &spender, &spender));
+        }
+        let mut approvals_received = Vec::new(&env);
+        let grantors: Vec<Address> = Self::store(&env)
+            .get(&ExtKey::Grantors(owner.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        for grantor in Self::page_of(&grantors, start, page_size).iter() {
+            approvals_received.push_back(Self::approval(&env, &grantor, &owner, &grantor));
+        }
+
+        AddressStatement {
+            balance: Self::get_balance(&env, &owner),
+            locked_balance: Self::get_locked_balance(&env, &owner),
+            fees_paid: Self::store(&env)
+                .get(&DataKey::FeesPaid(owner.clone()))
+                .unwrap_or(0),
+            frozen: Self::is_frozen(env.clone(), owner.clone()),
+            series_count: positions.len(),
+            series,
+            approvals_granted,
+            approvals_received,
+            owner,
+        }
+    }
+
+    /// Get a page of registered facilities, in registration order
+    pub fn list_facilities(env: Env, page: u32, page_size: u32) -> Vec<FacilityView> {
+        let names = Self::facility_names(&env);
+        let page_size = page_size.min(MAX_FACILITY_PAGE);
+        let start = page.saturating_mul(page_size);
+        let end = names.len().min(start.saturating_add(page_size));
+
+        let mut views = Vec::new(&env);
+        for index in start..end {
+            if let Some(name) = names.get(index) {
+                views.push_back(Self::facility_view(&env, name));
+            }
+        }
+        views
+    }
+
+    /// Get the registry view of a facility, None if it was never registered
+    pub fn facility(env: Env, name: String) -> Option<FacilityView> {
+        if !Self::store(&env).has(&DataKey::Facility(name.clone())) {
+            return None;
+        }
+        Some(Self::facility_view(&env, name))
+    }
+}
+
+impl ContangoToken {
+    /// Add to the per-kind totals behind `burn_breakdown`
+    pub(super) fn tally_burn(env: &Env, kind: BurnKind, amount: i128) {
+        let mut breakdown = Self::burn_breakdown(env.clone());
+        let burned = breakdown.get(kind).unwrap_or(0);
+        breakdown.set(kind, checked_add(env, burned, amount));
+        Self::store(env).set(&DataKey::BurnBreakdown, &breakdown);
+    }
+
+    /// Record an operation in the ring buffer, overwriting the oldest slot
+    pub(super) fn record_op(
+        env: &Env,
+        seq: u64,
+        op_kind: &str,
+        actor: &Address,
+        series: Option<String>,
+        amount: i128,
+    ) {
+        Self::store(env).set(
+            &DataKey::OpLog(seq % OP_LOG_SIZE),
+            &OpRecord {
+                seq,
+                op_kind: Symbol::new(env, op_kind),
+                actor: actor.clone(),
+                series,
+                amount,
+                ledger: env.ledger().sequence(),
+            },
+        );
+    }
+
+    /// Add `item` to the list kept under `key`, if not already there
+    pub(super) fn index_insert<T: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        key: &ExtKey,
+        item: T,
+    ) {
+        let mut items: Vec<T> = Self::store(env).get(key).unwrap_or_else(|| Vec::new(env));
+        if !items.contains(&item) {
+            items.push_back(item);
+            Self::store(env).set(key, &items);
+        }
+    }
+
+    pub(super) fn index_remove<T: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        key: &ExtKey,
+        item: T,
+    ) {
+        let mut items: Vec<T> = match Self::store(env).get(key) {
+            Some(items) => items,
+            None => return,
+        };
+        if let Some(index) = items.first_index_of(&item) {
+            items.remove(index);
+            if items.is_empty() {
+                Self::store(env).remove(key);
+            } else {
+                Self::store(env).set(key, &items);
+            }
+        }
+    }
+
+    /// Keep a series in the buyer's locked index while it has tokens locked or awaiting acceptance
+    pub(super) fn sync_locked_index(env: &Env, buyer: &Address, series_id: &String) {
+        let key = ExtKey::LockedSeries(buyer.clone());
+        let awaiting = Self::store(env)
+            .get::<PendingDelivery>(&DataKey::PendingDelivery(series_id.clone()))
+            .is_some_and(|pending| pending.buyer == *buyer);
+        if awaiting || Self::series_locked(env, series_id) > 0 {
+            Self::index_insert(env, &key, series_id.clone());
+        } else {
+            Self::index_remove(env, &key, series_id.clone());
+        }
+    }
+
+    pub(super) fn record_fee_paid(env: &Env, payer: &Address, fee: i128) {
+        if fee == 0 {
+            return;
+        }
+        let key = DataKey::FeesPaid(payer.clone());
+        let paid: i128 = Self::store(env).get(&key).unwrap_or(0);
+        Self::store(env).set(&key, &checked_add(env, paid, fee));
+    }
+
+    /// A stored reason; entries written before reasons were recorded hold a bare flag
+    fn stored_reason(env: &Env, key: &DataKey) -> Option<Reason> {
+        let val: Val = Self::store(env).get(key)?;
+        Reason::try_from_val(env, &val).ok()
+    }
+
+    /// `items[start..start + size]`, clipped to the list
+    pub(super) fn page_of<T: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        items: &Vec<T>,
+        start: u32,
+        size: u32,
+    ) -> Vec<T> {
+        let end = items.len().min(start.saturating_add(size));
+        if start >= end {
+            return Vec::new(items.env());
+        }
+        items.slice(start..end)
+    }
+
+    /// The allowance `from` gave `spender`, shown against `counterparty`
+    pub(super) fn approval(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        counterparty: &Address,
+    ) -> Approval {
+        let allowance: Option<AllowanceValue> =
+            Self::store(env).get(&DataKey::Allowance(AllowanceDataKey {
+                from: from.clone(),
+                spender: spender.clone(),
+            }));
+        Approval {
+            counterparty: counterparty.clone(),
+            amount: Self::allowance(env.clone(), from.clone(), spender.clone()),
+            expiration_ledger: allowance.map_or(0, |a| a.expiration_ledger),
+        }
+    }
+
+    pub(super) fn facility_view(env: &Env, name: String) -> FacilityView {
+        let record = Self::get_facility_record(env, &name);
+        FacilityView {
+            active: !Self::store(env).has(&DataKey::FacilityInactive(name.clone())),
+            name,
+            operator: record.operator,
+            capacity_kg: record.capacity_kg,
+            backed_kg: record.backed_kg,
+        }
+    }
+
+    /// Stamps of a series, zeros for series created before stamps were recorded
+    pub(super) fn get_series_stamps(env: &Env, series_id: &String) -> SeriesStamps {
+        Self::store(env)
+            .get(&DataKey::SeriesStamps(series_id.clone()))
+            .unwrap_or(SeriesStamps {
+                created_ledger: 0,
+                created_at: 0,
+                updated_at: 0,
+            })
+    }
+}
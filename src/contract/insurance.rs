@@ -0,0 +1,80 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, SettingChange,
+    checked_add, checked_bps,
+};
+use crate::config::Config;
+use soroban_sdk::{Address, Env, String, Symbol, contractimpl, panic_with_error};
+
+#[contractimpl]
+impl ContangoToken {
+    /// Set the share of each mint held back in the insurance buffer (admin only)
+    pub fn set_insurance_reserve(env: Env, reserve_bps: u32) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if reserve_bps > 500 {
+            // Max 5%
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+
+        config.insurance_reserve_bps = reserve_bps;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, reserve_bps as i128)
+    }
+
+    /// Compensate a buyer from the insurance buffer (admin and the series' guarantee agent)
+    pub fn pay_insurance(env: Env, series_id: String, to: Address, amount: i128) {
+        let config = Self::load_config(&env);
+        let metadata = Self::require_series(&env, &series_id);
+        config.admin.require_auth();
+        Self::require_guarantee_agent(&env, &metadata).require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(env, ContractError::InvalidAmount);
+        }
+        let buffer = Self::insurance_buffer(env.clone());
+        if amount > buffer {
+            panic_with_error!(env, ContractError::InsufficientInsuranceBuffer);
+        }
+
+        Self::store(&env).set(&DataKey::InsuranceBuffer, &(buffer - amount));
+        Self::increase_balance(&env, &to, &series_id, amount);
+
+        Self::log_op(&env, "pay_insurance", &to, Some(series_id.clone()), amount);
+
+        env.events()
+            .publish((Symbol::new(&env, "insurance_paid"), series_id, to), amount);
+    }
+
+    /// Get the tokens held in the insurance buffer
+    pub fn insurance_buffer(env: Env) -> i128 {
+        Self::store(&env)
+            .get(&DataKey::InsuranceBuffer)
+            .unwrap_or(0)
+    }
+}
+
+impl ContangoToken {
+    /// Divert the configured slice of a mint into the insurance buffer
+    pub(super) fn reserve_insurance(
+        env: &Env,
+        config: &Config,
+        series_id: &String,
+        amount: i128,
+    ) -> i128 {
+        let reserve = checked_bps(env, amount, config.insurance_reserve_bps as i128);
+        if reserve > 0 {
+            let buffer = Self::insurance_buffer(env.clone());
+            Self::store(env).set(
+                &DataKey::InsuranceBuffer,
+                &checked_add(env, buffer, reserve),
+            );
+            env.events().publish(
+                (Symbol::new(env, "insurance_reserved"), series_id.clone()),
+                reserve,
+            );
+        }
+        reserve
+    }
+}
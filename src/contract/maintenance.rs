@@ -0,0 +1,198 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, ExtKey, Role,
+    SettingChange, TokenState,
+};
+use soroban_sdk::{Address, Env, String, Symbol, Vec, contractimpl, panic_with_error, vec};
+
+/// Upper bound on holders, and on series, moved by a single `migrate_storage`
+pub const MAX_MIGRATION_BATCH: u32 = 10;
+
+#[contractimpl]
+impl ContangoToken {
+    /// Set when holder and series entries get their TTL extended, and by how much (admin only).
+    /// Entries left with fewer than `threshold` ledgers are extended to `extend_to`.
+    pub fn set_ttl_policy(env: Env, threshold: u32, extend_to: u32) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if threshold > extend_to || extend_to > env.storage().max_ttl() {
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+
+        config.ttl_threshold = threshold;
+        config.ttl_extend_to = extend_to;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, "ttl_policy_changed"),),
+            (threshold, extend_to),
+        );
+        Self::setting_applied(&env, extend_to as i128)
+    }
+
+    /// Move what an older deployment kept in instance storage to its own entries (admin only):
+    /// the legacy state, the facilities, the given holders' balances, accounting, freezes and
+    /// roles, and the series created at `start..start + limit`. At most `MAX_MIGRATION_BATCH`
+    /// holders and series per call; repeating a call is harmless. Returns where the next page
+    /// of series starts. Allowances move on their next write.
+    pub fn migrate_storage(env: Env, holders: Vec<Address>, start: u32, limit: u32) -> u32 {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+        if holders.len() > MAX_MIGRATION_BATCH {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+        let store = Self::store(&env);
+
+        if let Some(legacy) = Self::legacy_state(&env) {
+            for (index, (series_id, metadata)) in legacy.series.iter().enumerate() {
+                let index_key = DataKey::SeriesByIndex(index as u32);
+                if !store.has(&index_key) {
+                    store.set(&index_key, &series_id);
+                }
+                if Self::load_series(&env, &series_id).is_none() {
+                    Self::store_series(&env, &series_id, &metadata);
+                }
+            }
+            if !store.has(&DataKey::SeriesCount) {
+                store.set(&DataKey::SeriesCount, &legacy.series.len());
+            }
+            store.set(
+                &DataKey::State,
+                &TokenState {
+                    total_supply: legacy.total_supply,
+                },
+            );
+        }
+
+        store.relocate(&DataKey::FacilityNames);
+        for facility in Self::facility_names(&env).iter() {
+            for key in Self::facility_keys(&facility) {
+                store.relocate(&key);
+            }
+        }
+
+        for holder in holders.iter() {
+            for key in Self::holder_keys(&env, &holder) {
+                store.relocate(&key);
+            }
+        }
+
+        let end =
+            Self::series_count(&env).min(start.saturating_add(limit.min(MAX_MIGRATION_BATCH)));
+        for index in start..end {
+            store.relocate(&DataKey::SeriesByIndex(index));
+            let Some(series_id) = store.get::<String>(&DataKey::SeriesByIndex(index)) else {
+                continue;
+            };
+            for key in Self::series_keys(&series_id) {
+                store.relocate(&key);
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "storage_migrated"),),
+            (holders.len(), start, end),
+        );
+        end
+    }
+
+    /// Extend the TTL of an address's balances and holdings, so idle ones are not archived (anyone)
+    pub fn bump_balance(env: Env, addr: Address) {
+        let store = Self::store(&env);
+        for key in Self::holder_keys(&env, &addr) {
+            store.bump(&key);
+        }
+        for key in [
+            ExtKey::LockedSeries(addr.clone()),
+            ExtKey::Spenders(addr.clone()),
+            ExtKey::Grantors(addr),
+        ] {
+            store.bump(&key);
+        }
+    }
+
+    /// Extend the TTL of a facility's record and settings (anyone)
+    pub fn bump_facility(env: Env, facility: String) {
+        let store = Self::store(&env);
+        store.bump(&DataKey::FacilityNames);
+        for key in Self::facility_keys(&facility) {
+            store.bump(&key);
+        }
+    }
+
+    /// Extend the TTL of a series' metadata and records (anyone)
+    pub fn bump_series(env: Env, series_id: String) {
+        let store = Self::store(&env);
+        if !store.has(&DataKey::SeriesMetadata(series_id.clone())) {
+            panic_with_error!(env, ContractError::SeriesNotFound);
+        }
+        for key in Self::series_keys(&series_id) {
+            store.bump(&key);
+        }
+        store.bump(&ExtKey::PayoutRoute(series_id.clone()));
+        store.bump(&ExtKey::DisputeReason(series_id.clone()));
+        store.bump(&ExtKey::Settled(series_id));
+    }
+}
+
+impl ContangoToken {
+    /// An address's own entries, its roles, and its share of each series it holds
+    fn holder_keys(env: &Env, holder: &Address) -> Vec<DataKey> {
+        let mut keys = vec![
+            env,
+            DataKey::Balance(holder.clone()),
+            DataKey::LockedBalance(holder.clone()),
+            DataKey::FeesPaid(holder.clone()),
+            DataKey::ProducerQuota(holder.clone()),
+            DataKey::ProducerRecord(holder.clone()),
+            DataKey::AgentPending(holder.clone()),
+            DataKey::HeldSeries(holder.clone()),
+            DataKey::Frozen(holder.clone()),
+            DataKey::FeeExempt(holder.clone()),
+        ];
+        for role in [
+            Role::Minter,
+            Role::FeeManager,
+            Role::Pauser,
+            Role::DeliveryValidator,
+        ] {
+            keys.push_back(DataKey::Role(role, holder.clone()));
+        }
+        for facility in Self::facility_names(env).iter() {
+            keys.push_back(DataKey::FacilityValidator(facility, holder.clone()));
+        }
+        for series_id in Self::get_held_series(env, holder).iter() {
+            keys.push_back(DataKey::SeriesBalance(series_id.clone(), holder.clone()));
+            keys.push_back(DataKey::AssistedBurn(series_id, holder.clone()));
+        }
+        keys
+    }
+
+    /// Entries kept per facility
+    fn facility_keys(facility: &String) -> [DataKey; 3] {
+        [
+            DataKey::Facility(facility.clone()),
+            DataKey::ConfirmationWindow(facility.clone()),
+            DataKey::FacilityInactive(facility.clone()),
+        ]
+    }
+
+    /// Entries kept per series
+    fn series_keys(series_id: &String) -> [DataKey; 13] {
+        [
+            DataKey::SeriesMetadata(series_id.clone()),
+            DataKey::PendingDelivery(series_id.clone()),
+            DataKey::SeriesAllowlist(series_id.clone()),
+            DataKey::SeriesStamps(series_id.clone()),
+            DataKey::SeriesMinter(series_id.clone()),
+            DataKey::SeriesStatus(series_id.clone()),
+            DataKey::AgentEscrow(series_id.clone()),
+            DataKey::InventoryAttestation(series_id.clone()),
+            DataKey::SeriesSupply(series_id.clone()),
+            DataKey::TokenRecipient(series_id.clone()),
+            DataKey::SeriesLocked(series_id.clone()),
+            DataKey::SeriesMinted(series_id.clone()),
+            DataKey::PaymentEscrow(series_id.clone()),
+        ]
+    }
+}
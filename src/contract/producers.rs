@@ -0,0 +1,234 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, Distribution,
+    SeriesMetadata, SettingChange, checked_add, checked_sub,
+};
+use soroban_sdk::{Address, Env, String, Symbol, contractimpl, contracttype, panic_with_error};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProducerQuota {
+    pub max_outstanding: i128,
+    pub outstanding: i128, // Self-minted supply not yet burned
+}
+
+/// Append-only delivery track record of a producer
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProducerRecord {
+    pub on_time: u32,
+    pub late: u32,
+    pub defaulted: u32,
+    pub total_kg_delivered: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InventoryAttestation {
+    pub facility: String,
+    pub kg_on_hand: u64,
+    pub attestation_hash: String, // Hash of the signed stock report
+    pub attested_at: u64,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Mint spot tokens for the producer's own series within their quota (facility co-signs)
+    pub fn producer_mint_spot(
+        env: Env,
+        producer: Address,
+        series_id: String,
+        metadata: SeriesMetadata,
+        distribution: Distribution,
+        amount: i128,
+    ) {
+        Self::require_not_paused(&env);
+        Self::require_minting_enabled(&env);
+        let config = Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        producer.require_auth();
+
+        let foreign_series = Self::get_series(env.clone(), series_id.clone())
+            .is_some_and(|existing| existing.producer != producer);
+        if metadata.producer != producer || foreign_series {
+            panic_with_error!(env, ContractError::Unauthorized);
+        }
+
+        Self::get_facility_record(&env, &metadata.storage_facility)
+            .operator
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::FacilityHasNoOperator))
+            .require_auth();
+
+        let key = DataKey::ProducerQuota(producer.clone());
+        let mut quota: ProducerQuota = Self::store(&env)
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NoMintQuota));
+        quota.outstanding = checked_add(&env, quota.outstanding, amount);
+        if quota.outstanding > quota.max_outstanding {
+            panic_with_error!(env, ContractError::ProducerQuotaExceeded);
+        }
+
+        Self::do_mint_spot(
+            &env,
+            &config,
+            &producer,
+            series_id.clone(),
+            metadata,
+            distribution,
+            amount,
+        );
+        Self::store(&env).set(&key, &quota);
+        Self::store(&env).set(&DataKey::SeriesMinter(series_id), &producer);
+    }
+
+    /// Allow a producer to self-mint up to an outstanding amount, 0 to revoke (admin only)
+    pub fn set_producer_quota(env: Env, producer: Address, max_outstanding: i128) -> SettingChange {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let key = DataKey::ProducerQuota(producer);
+        let outstanding = Self::store(&env)
+            .get::<ProducerQuota>(&key)
+            .map_or(0, |quota| quota.outstanding);
+        Self::store(&env).set(
+            &key,
+            &ProducerQuota {
+                max_outstanding,
+                outstanding,
+            },
+        );
+
+        Self::setting_applied(&env, max_outstanding)
+    }
+
+    /// Get a producer's self-mint quota and usage
+    pub fn get_producer_quota(env: Env, producer: Address) -> Option<ProducerQuota> {
+        Self::store(&env).get(&DataKey::ProducerQuota(producer))
+    }
+
+    /// Attest the stock physically backing a series (facility operator)
+    pub fn attest_inventory(
+        env: Env,
+        facility: String,
+        series_id: String,
+        kg_on_hand: u64,
+        attestation_hash: String,
+    ) {
+        Self::ensure_initialized(&env);
+        let metadata = Self::require_series(&env, &series_id);
+        if metadata.storage_facility != facility {
+            panic_with_error!(env, ContractError::SeriesNotAtFacility);
+        }
+        Self::get_facility_record(&env, &facility)
+            .operator
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::FacilityHasNoOperator))
+            .require_auth();
+
+        Self::store(&env).set(
+            &DataKey::InventoryAttestation(series_id.clone()),
+            &InventoryAttestation {
+                facility,
+                kg_on_hand,
+                attestation_hash: attestation_hash.clone(),
+                attested_at: env.ledger().timestamp(),
+            },
+        );
+
+        if kg_on_hand < metadata.quantity_kg {
+            env.events().publish(
+                (Symbol::new(&env, "backing_shortfall"), series_id.clone()),
+                (metadata.quantity_kg, kg_on_hand),
+            );
+        }
+        env.events().publish(
+            (Symbol::new(&env, "inventory_attested"), series_id),
+            (kg_on_hand, attestation_hash),
+        );
+    }
+
+    /// Pause series movements once their latest attestation is older than this, 0 = off (admin only)
+    pub fn set_attestation_max_age(env: Env, max_age_secs: u64) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        config.attestation_max_age_secs = max_age_secs;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, max_age_secs as i128)
+    }
+
+    /// Get the latest inventory attestation for a series
+    pub fn latest_attestation(env: Env, series_id: String) -> Option<InventoryAttestation> {
+        Self::store(&env).get(&DataKey::InventoryAttestation(series_id))
+    }
+
+    /// Check whether a series lacks an attestation newer than `max_age_secs`
+    pub fn is_backing_stale(env: Env, series_id: String, max_age_secs: u64) -> bool {
+        match Self::latest_attestation(env.clone(), series_id) {
+            Some(attestation) => {
+                env.ledger()
+                    .timestamp()
+                    .saturating_sub(attestation.attested_at)
+                    > max_age_secs
+            }
+            None => true,
+        }
+    }
+
+    /// Get a producer's delivery track record
+    pub fn producer_record(env: Env, producer: Address) -> ProducerRecord {
+        Self::store(&env)
+            .get(&DataKey::ProducerRecord(producer))
+            .unwrap_or_default()
+    }
+}
+
+impl ContangoToken {
+    /// Release producer quota when tokens of a self-minted series are burned
+    pub(super) fn release_producer_quota(env: &Env, series_id: &String, amount: i128) {
+        let producer: Address =
+            match Self::store(env).get(&DataKey::SeriesMinter(series_id.clone())) {
+                Some(p) => p,
+                None => return,
+            };
+
+        let key = DataKey::ProducerQuota(producer);
+        if let Some(mut quota) = Self::store(env).get::<ProducerQuota>(&key) {
+            quota.outstanding = checked_sub(env, quota.outstanding, amount).max(0);
+            Self::store(env).set(&key, &quota);
+        }
+    }
+
+    /// Series with stale inventory attestations are paused when auto-pause is on
+    pub(super) fn require_fresh_backing(env: &Env, series_id: &String) {
+        if let Err(reason) = Self::backing_status(env, series_id) {
+            panic_with_error!(env, reason);
+        }
+    }
+
+    pub(super) fn backing_status(env: &Env, series_id: &String) -> Result<(), ContractError> {
+        let max_age = Self::load_config(env).attestation_max_age_secs;
+        if max_age > 0 && Self::is_backing_stale(env.clone(), series_id.clone(), max_age) {
+            return Err(ContractError::SeriesPaused);
+        }
+        Ok(())
+    }
+
+    /// Count a completed delivery on the producer's track record
+    pub(super) fn record_producer_delivery(env: &Env, metadata: &SeriesMetadata) {
+        let mut record = Self::producer_record(env.clone(), metadata.producer.clone());
+        if env.ledger().timestamp() > metadata.delivery_date {
+            record.late += 1;
+        } else {
+            record.on_time += 1;
+        }
+        record.total_kg_delivered += metadata.quantity_kg;
+        Self::store(env).set(&DataKey::ProducerRecord(metadata.producer.clone()), &record);
+    }
+
+    /// Count a default against the producer's track record
+    pub(super) fn record_producer_default(env: &Env, producer: &Address) {
+        let mut record = Self::producer_record(env.clone(), producer.clone());
+        record.defaulted += 1;
+        Self::store(env).set(&DataKey::ProducerRecord(producer.clone()), &record);
+    }
+}
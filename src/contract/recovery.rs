@@ -0,0 +1,184 @@
+use super::{
+    BurnKind, ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey,
+    checked_add_u64, checked_sub,
+};
+use soroban_sdk::{Address, Env, String, Symbol, contractimpl, contracttype, panic_with_error};
+
+/// Window in which a holder can veto an assisted burn of their tokens
+pub const ASSISTED_BURN_DELAY_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AssistedBurn {
+    pub amount: i128,
+    pub justification_hash: String, // Hash of the off-chain ownership evidence
+    pub eta: u64,                   // Earliest timestamp the burn can execute
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Propose burning tokens from a holder who lost their keys (admin and facility operator)
+    pub fn assisted_burn(
+        env: Env,
+        series_id: String,
+        holder: Address,
+        amount: i128,
+        justification_hash: String,
+    ) {
+        let config = Self::load_config(&env);
+        let metadata = Self::require_series(&env, &series_id);
+        config.admin.require_auth();
+        Self::get_facility_record(&env, &metadata.storage_facility)
+            .operator
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::FacilityHasNoOperator))
+            .require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(env, ContractError::InvalidAmount);
+        }
+
+        let key = DataKey::AssistedBurn(series_id.clone(), holder.clone());
+        if Self::store(&env).has(&key) {
+            panic_with_error!(env, ContractError::AlreadyPending);
+        }
+
+        let eta = checked_add_u64(&env, env.ledger().timestamp(), ASSISTED_BURN_DELAY_SECS);
+        Self::store(&env).set(
+            &key,
+            &AssistedBurn {
+                amount,
+                justification_hash: justification_hash.clone(),
+                eta,
+            },
+        );
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "assisted_burn_proposed"),
+                series_id,
+                holder,
+            ),
+            (amount, justification_hash, eta),
+        );
+    }
+
+    /// Veto a pending assisted burn of the caller's tokens
+    pub fn veto_assisted_burn(env: Env, series_id: String, holder: Address) {
+        Self::ensure_initialized(&env);
+        holder.require_auth();
+
+        let key = DataKey::AssistedBurn(series_id.clone(), holder.clone());
+        let pending = Self::get_assisted_burn(&env, &key);
+        Self::store(&env).remove(&key);
+
+        env.events().publish(
+            (Symbol::new(&env, "assisted_burn_vetoed"), series_id, holder),
+            pending.amount,
+        );
+    }
+
+    /// Execute an assisted burn once the veto window has elapsed (anyone)
+    pub fn execute_assisted_burn(env: Env, series_id: String, holder: Address) {
+        Self::require_not_paused(&env);
+        Self::ensure_initialized(&env);
+        let key = DataKey::AssistedBurn(series_id.clone(), holder.clone());
+        let pending = Self::get_assisted_burn(&env, &key);
+
+        if env.ledger().timestamp() < pending.eta {
+            panic_with_error!(env, ContractError::TimelockNotExpired);
+        }
+
+        Self::decrease_balance(&env, &holder, &series_id, pending.amount);
+        let mut state = Self::get_state(&env);
+        state.total_supply = checked_sub(&env, state.total_supply, pending.amount);
+        Self::put_state(&env, &state);
+        Self::release_producer_quota(&env, &series_id, pending.amount);
+        Self::adjust_series_supply(&env, &series_id, -pending.amount);
+        Self::record_burn(&env, BurnKind::Correction, pending.amount);
+        Self::store(&env).remove(&key);
+
+        Self::log_op(
+            &env,
+            "assisted_burn",
+            &holder,
+            Some(series_id.clone()),
+            pending.amount,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "assisted_burn"), series_id, holder),
+            (pending.amount, pending.justification_hash),
+        );
+    }
+
+    /// Recover tokens of a series from a holder, e.g. after fraud or a court order (admin only)
+    pub fn clawback(env: Env, from: Address, series_id: String, amount: i128) {
+        let config = Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        config.admin.require_auth();
+        Self::require_series(&env, &series_id);
+
+        Self::decrease_balance(&env, &from, &series_id, amount);
+        Self::retire_clawback(&env, &from, &series_id, amount, false);
+    }
+
+    /// Recover tokens still locked for a future's buyer (admin only)
+    pub fn clawback_locked(env: Env, from: Address, series_id: String, amount: i128) {
+        let config = Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        config.admin.require_auth();
+
+        let metadata = Self::require_series(&env, &series_id);
+        if metadata.buyer.as_ref() != Some(&from) {
+            panic_with_error!(env, ContractError::NoLockedTokens);
+        }
+        if Self::locked_in_series(&env, &series_id, &from) < amount {
+            panic_with_error!(env, ContractError::InsufficientBalance);
+        }
+
+        Self::decrease_locked_balance(&env, &from, &series_id, amount);
+        Self::retire_clawback(&env, &from, &series_id, amount, true);
+    }
+
+    /// Get an assisted burn awaiting its veto window
+    pub fn pending_assisted_burn(
+        env: Env,
+        series_id: String,
+        holder: Address,
+    ) -> Option<AssistedBurn> {
+        Self::store(&env).get(&DataKey::AssistedBurn(series_id, holder))
+    }
+}
+
+impl ContangoToken {
+    pub(super) fn get_assisted_burn(env: &Env, key: &DataKey) -> AssistedBurn {
+        Self::store(env)
+            .get(key)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NothingPending))
+    }
+
+    pub(super) fn retire_clawback(
+        env: &Env,
+        from: &Address,
+        series_id: &String,
+        amount: i128,
+        locked: bool,
+    ) {
+        let mut state = Self::get_state(env);
+        state.total_supply = checked_sub(env, state.total_supply, amount);
+        Self::put_state(env, &state);
+        Self::release_producer_quota(env, series_id, amount);
+        Self::adjust_series_supply(env, series_id, -amount);
+        Self::record_burn(env, BurnKind::Clawback, amount);
+
+        Self::log_op(env, "clawback", from, Some(series_id.clone()), amount);
+        env.events().publish(
+            (
+                Symbol::new(env, "clawback"),
+                series_id.clone(),
+                from.clone(),
+            ),
+            (amount, locked),
+        );
+    }
+}
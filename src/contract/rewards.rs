@@ -0,0 +1,53 @@
+use super::{ContangoToken, ContangoTokenArgs, ContangoTokenClient, DataKey};
+use soroban_sdk::{Address, Env, IntoVal, String, Symbol, contractimpl, vec};
+
+#[contractimpl]
+impl ContangoToken {
+    /// Set or clear the rewards contract notified of fee-bearing transfers (admin only)
+    pub fn set_rewards_hook(env: Env, hook: Option<Address>) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        match hook {
+            Some(addr) => Self::store(&env).set(&DataKey::RewardsHook, &addr),
+            None => Self::store(&env).remove(&DataKey::RewardsHook),
+        }
+    }
+}
+
+impl ContangoToken {
+    /// Best-effort notification of the rewards contract; a failing hook never reverts the transfer
+    pub(super) fn notify_rewards_hook(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+        series_id: Option<String>,
+    ) {
+        let hook: Address = match Self::store(env).get(&DataKey::RewardsHook) {
+            Some(h) => h,
+            None => return,
+        };
+
+        let result = Self::call_out(env, || {
+            env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &hook,
+                &Symbol::new(env, "notify_transfer"),
+                vec![
+                    env,
+                    from.into_val(env),
+                    to.into_val(env),
+                    amount.into_val(env),
+                    series_id.into_val(env),
+                ],
+            )
+        });
+
+        if !matches!(result, Ok(Ok(()))) {
+            env.events().publish(
+                (Symbol::new(env, "hook_failed"), hook),
+                (from.clone(), to.clone(), amount),
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use super::{ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey};
+use soroban_sdk::{Address, Env, Symbol, contractimpl, contracttype, panic_with_error};
+
+/// Permissions the admin can hand out; the admin holds every role implicitly
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Minter,            // mint_spot, mint_additional, mint_future
+    FeeManager,        // Transfer, burn, platform and storage fee percents
+    Pauser,            // pause, unpause
+    DeliveryValidator, // Confirms deliveries at every facility
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Give an address a role (admin only)
+    pub fn grant_role(env: Env, role: Role, addr: Address) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        Self::store(&env).set(&DataKey::Role(role, addr.clone()), &true);
+        env.events()
+            .publish((Symbol::new(&env, "role_granted"), role, addr), ());
+    }
+
+    /// Take a role away from an address (admin only)
+    pub fn revoke_role(env: Env, role: Role, addr: Address) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        Self::store(&env).remove(&DataKey::Role(role, addr.clone()));
+        env.events()
+            .publish((Symbol::new(&env, "role_revoked"), role, addr), ());
+    }
+
+    /// Check whether an address holds a role, granted or as admin
+    pub fn has_role(env: Env, role: Role, addr: Address) -> bool {
+        addr == Self::load_config(&env).admin || Self::store(&env).has(&DataKey::Role(role, addr))
+    }
+}
+
+impl ContangoToken {
+    /// Require `addr` to authorize and to hold `role`
+    pub(super) fn require_role(env: &Env, role: Role, addr: &Address) {
+        addr.require_auth();
+        if !Self::has_role(env.clone(), role, addr.clone()) {
+            panic_with_error!(env, ContractError::Unauthorized);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, Distribution,
+    Role, SeriesLot,
+};
+use soroban_sdk::{Address, Env, String, Symbol, contractimpl, contracttype, panic_with_error};
+
+/// Amendable series fields; binding terms are deliberately absent
+#[contracttype]
+#[derive(Clone)]
+pub struct MetadataUpdate {
+    pub location: Option<String>,
+    pub metadata_uri: Option<String>,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Mint more tokens into an existing spot series under its stored metadata (Minter role)
+    pub fn mint_additional(
+        env: Env,
+        minter: Address,
+        series_id: String,
+        distribution: Distribution,
+        amount: i128,
+    ) {
+        Self::require_not_paused(&env);
+        Self::require_minting_enabled(&env);
+        let config = Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        Self::require_role(&env, Role::Minter, &minter);
+
+        let metadata = Self::require_series(&env, &series_id);
+        if metadata.is_future {
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+        Self::do_mint_spot(
+            &env,
+            &config,
+            &minter,
+            series_id,
+            metadata,
+            distribution,
+            amount,
+        );
+    }
+
+    /// Redirect a future's tokens at delivery to another address (buyer only, before delivery)
+    pub fn set_token_recipient(env: Env, series_id: String, recipient: Address) {
+        Self::ensure_initialized(&env);
+        let metadata = Self::require_deliverable(&env, &series_id);
+        let buyer = Self::require_buyer(&env, &metadata);
+        buyer.require_auth();
+
+        let old = Self::resolve_token_recipient(&env, &series_id, &buyer);
+        if recipient == buyer {
+            Self::store(&env).remove(&DataKey::TokenRecipient(series_id.clone()));
+        } else {
+            Self::store(&env).set(&DataKey::TokenRecipient(series_id.clone()), &recipient);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "token_recipient_changed"), series_id),
+            (old, recipient),
+        );
+    }
+
+    /// Get the address credited when a future is delivered
+    pub fn token_recipient(env: Env, series_id: String) -> Option<Address> {
+        let buyer = Self::get_series(env.clone(), series_id.clone())?.buyer?;
+        Some(Self::resolve_token_recipient(&env, &series_id, &buyer))
+    }
+
+    /// Amend the non-binding metadata of a series (admin only)
+    pub fn amend_series(env: Env, series_id: String, update: MetadataUpdate) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        Self::require_series(&env, &series_id);
+        Self::apply_metadata_update(&env, &series_id, &update);
+
+        env.events()
+            .publish((Symbol::new(&env, "series_amended"),), series_id);
+    }
+
+    /// Move custody of a series to another facility (admin only)
+    pub fn transfer_custody(env: Env, series_id: String, new_facility: String) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let mut metadata = Self::require_series(&env, &series_id);
+        let old_facility = metadata.storage_facility.clone();
+
+        Self::release_facility_kg(&env, &old_facility, metadata.quantity_kg);
+        Self::add_facility_kg(&env, &config, &new_facility, metadata.quantity_kg);
+
+        metadata.storage_facility = new_facility.clone();
+        Self::store_series(&env, &series_id, &metadata);
+        Self::stamp_series(&env, &series_id, &metadata);
+
+        env.events().publish(
+            (Symbol::new(&env, "custody_transferred"), series_id),
+            (old_facility, new_facility),
+        );
+    }
+
+    /// Re-certify the kg backing a series, resetting its mint headroom to what is
+    /// not outstanding (admin only)
+    pub fn adjust_series_quantity(env: Env, series_id: String, new_quantity_kg: u64) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let mut metadata = Self::require_series(&env, &series_id);
+        let supply = Self::series_supply(env.clone(), series_id.clone());
+        if (new_quantity_kg as i128) < supply {
+            panic_with_error!(env, ContractError::SeriesAtCapacity);
+        }
+
+        // Bagged series keep their bag size and recount the bags
+        if let SeriesLot::Bagged(lot) = &mut metadata.lot {
+            if lot.bag_kg == 0 || !new_quantity_kg.is_multiple_of(lot.bag_kg as u64) {
+                panic_with_error!(env, ContractError::InvalidLotInfo);
+            }
+            lot.bag_count = u32::try_from(new_quantity_kg / lot.bag_kg as u64)
+                .unwrap_or_else(|_| panic_with_error!(env, ContractError::InvalidLotInfo));
+        }
+
+        let old_quantity_kg = metadata.quantity_kg;
+        Self::release_facility_kg(&env, &metadata.storage_facility, old_quantity_kg);
+        Self::add_facility_kg(&env, &config, &metadata.storage_facility, new_quantity_kg);
+
+        metadata.quantity_kg = new_quantity_kg;
+        Self::store_series(&env, &series_id, &metadata);
+        Self::stamp_series(&env, &series_id, &metadata);
+        Self::store(&env).set(&DataKey::SeriesMinted(series_id.clone()), &supply);
+
+        env.events().publish(
+            (Symbol::new(&env, "series_quantity_adjusted"), series_id),
+            (old_quantity_kg, new_quantity_kg),
+        );
+    }
+}
+
+impl ContangoToken {
+    pub(crate) fn apply_metadata_update(env: &Env, series_id: &String, update: &MetadataUpdate) {
+        let mut metadata = Self::require_series(env, series_id);
+        if let Some(location) = update.location.clone() {
+            metadata.location = location;
+        }
+        if let Some(uri) = update.metadata_uri.clone() {
+            let new_uri = Some(uri);
+            Self::validate_metadata_uri(env, &new_uri);
+            env.events().publish(
+                (Symbol::new(env, "metadata_uri_changed"), series_id.clone()),
+                (metadata.metadata_uri.clone(), new_uri.clone()),
+            );
+            metadata.metadata_uri = new_uri;
+        }
+
+        Self::store_series(env, series_id, &metadata);
+        Self::stamp_series(env, series_id, &metadata);
+    }
+}
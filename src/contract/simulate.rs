@@ -0,0 +1,285 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, ExtError, checked_add,
+    checked_sub,
+};
+use crate::config::Config;
+use soroban_sdk::{
+    Address, Env, Error, Map, String, Vec, contractimpl, contracttype, panic_with_error,
+};
+
+/// Upper bound on operations in a single simulation
+pub const MAX_SIM_OPS: u32 = 20;
+
+/// An operation that can be dry-run through `simulate`
+#[contracttype]
+#[derive(Clone)]
+pub enum SimOp {
+    Transfer(Address, Address, i128), // from, to, amount
+    Burn(Address, String, i128),      // from, series_id, amount
+    ConfirmDelivery(String),          // series_id
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SimResult {
+    Ok,
+    InsufficientBalance,
+    SeriesNotFound,
+    NotFutureContract,
+    NoLockedTokens,
+    AlreadyDelivered,
+    SeriesDefaulted,
+    SeriesDisputed,
+    SeriesCancelled,
+    Rejected(u32), // Any other error, by the code the real call fails with
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SimulationReport {
+    pub results: Vec<SimResult>,
+    pub balances: Map<Address, i128>, // Projected balances of every touched address
+    pub total_supply: i128,
+}
+
+/// Projected state of a simulation; storage is read the first time an entry is needed
+struct Shadow {
+    balances: Map<Address, i128>,
+    series_balances: Map<(Address, String), i128>,
+    held: Map<Address, Vec<String>>,
+    delivered: Map<String, bool>,
+    total_supply: i128,
+}
+
+impl Shadow {
+    fn balance(&self, env: &Env, addr: &Address) -> i128 {
+        self.balances
+            .get(addr.clone())
+            .unwrap_or_else(|| ContangoToken::get_balance(env, addr))
+    }
+
+    fn series_balance(&self, env: &Env, addr: &Address, series_id: &String) -> i128 {
+        self.series_balances
+            .get((addr.clone(), series_id.clone()))
+            .unwrap_or_else(|| ContangoToken::get_series_balance(env, addr, series_id))
+    }
+
+    fn held(&self, env: &Env, addr: &Address) -> Vec<String> {
+        self.held
+            .get(addr.clone())
+            .unwrap_or_else(|| ContangoToken::get_held_series(env, addr))
+    }
+
+    fn credit(&mut self, env: &Env, addr: &Address, series_id: &String, amount: i128) {
+        let balance = self.balance(env, addr);
+        let series_balance = self.series_balance(env, addr, series_id);
+        let mut held = self.held(env, addr);
+        if !held.contains(series_id) {
+            held.push_back(series_id.clone());
+            self.held.set(addr.clone(), held);
+        }
+        self.balances
+            .set(addr.clone(), checked_add(env, balance, amount));
+        self.series_balances.set(
+            (addr.clone(), series_id.clone()),
+            checked_add(env, series_balance, amount),
+        );
+    }
+
+    fn debit(&mut self, env: &Env, addr: &Address, series_id: &String, amount: i128) {
+        self.credit(env, addr, series_id, checked_sub(env, 0, amount));
+    }
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Dry-run a sequence of operations against projected balances without writing storage.
+    /// Ops apply in input order, each seeing the effects of the ones before; repeats are allowed.
+    /// Each op passes the same checks as its entry point, except who signs and, for deliveries,
+    /// which validator confirms.
+    pub fn simulate(env: Env, ops: Vec<SimOp>) -> SimulationReport {
+        if ops.len() > MAX_SIM_OPS {
+            panic_with_error!(env, ContractError::TooManyItems);
+        }
+
+        let config = Self::load_config(&env);
+        let mut shadow = Shadow {
+            balances: Map::new(&env),
+            series_balances: Map::new(&env),
+            held: Map::new(&env),
+            delivered: Map::new(&env),
+            total_supply: Self::get_state(&env).total_supply,
+        };
+        let mut results = Vec::new(&env);
+
+        for op in ops.iter() {
+            let outcome = match op {
+                SimOp::Transfer(from, to, amount) => {
+                    Self::simulate_transfer(&env, &config, &mut shadow, &from, &to, amount)
+                }
+                SimOp::Burn(from, series_id, amount) => {
+                    Self::simulate_burn(&env, &config, &mut shadow, &from, &series_id, amount)
+                }
+                SimOp::ConfirmDelivery(series_id) => {
+                    Self::simulate_delivery(&env, &config, &mut shadow, &series_id)
+                }
+            };
+            results.push_back(match outcome {
+                Ok(()) => SimResult::Ok,
+                Err(error) => Self::sim_result(error),
+            });
+        }
+
+        SimulationReport {
+            results,
+            balances: shadow.balances,
+            total_supply: shadow.total_supply,
+        }
+    }
+}
+
+impl ContangoToken {
+    /// `transfer`: oldest holdings first, skipping expired series, fee to the platform
+    fn simulate_transfer(
+        env: &Env,
+        config: &Config,
+        shadow: &mut Shadow,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        Self::pause_status(env)?;
+        Self::freeze_status(env, from)?;
+        Self::freeze_status(env, to)?;
+        if shadow.balance(env, from) < amount {
+            return Err(ContractError::InsufficientBalance.into());
+        }
+
+        let mut legs = Vec::new(env);
+        let mut remaining = amount;
+        for series_id in shadow.held(env, from).iter() {
+            if remaining == 0 {
+                break;
+            }
+            if Self::is_expired(env.clone(), series_id.clone()) {
+                continue;
+            }
+            if !Self::is_allowed_recipient(env, &series_id, to) {
+                return Err(ContractError::RecipientNotAllowed.into());
+            }
+            let leg = shadow.series_balance(env, from, &series_id).min(remaining);
+            legs.push_back((series_id, leg));
+            remaining -= leg;
+        }
+        if remaining > 0 {
+            return Err(ContractError::InsufficientBalance.into());
+        }
+
+        let mut fee_left = Self::transfer_fee(env, config, from, to, amount);
+        for (series_id, leg) in legs.iter() {
+            let leg_fee = fee_left.min(leg);
+            fee_left -= leg_fee;
+            shadow.debit(env, from, &series_id, leg);
+            shadow.credit(env, to, &series_id, leg - leg_fee);
+            shadow.credit(env, &config.admin, &series_id, leg_fee);
+        }
+        Ok(())
+    }
+
+    /// `burn_series`: redeemed from the series balance, fees to the platform and facility
+    fn simulate_burn(
+        env: &Env,
+        config: &Config,
+        shadow: &mut Shadow,
+        from: &Address,
+        series_id: &String,
+        amount: i128,
+    ) -> Result<(), Error> {
+        Self::pause_status(env)?;
+        Self::freeze_status(env, from)?;
+        let metadata = Self::load_series(env, series_id).ok_or(ContractError::SeriesNotFound)?;
+        Self::backing_status(env, series_id)?;
+        if shadow.series_balance(env, from, series_id) < amount {
+            return Err(ContractError::InsufficientBalance.into());
+        }
+
+        let (platform_fee, storage_fee) = Self::burn_fee_split(env, config, amount);
+        shadow.debit(env, from, series_id, amount);
+        shadow.credit(env, &config.admin, series_id, platform_fee);
+        for (recipient, share) in Self::storage_fee_split(
+            env,
+            &metadata.storage_facility,
+            &config.storage_address,
+            storage_fee,
+        ) {
+            shadow.credit(env, &recipient, series_id, share);
+        }
+        shadow.total_supply = checked_sub(
+            env,
+            shadow.total_supply,
+            amount - platform_fee - storage_fee,
+        );
+        Ok(())
+    }
+
+    /// `confirm_delivery` of everything still locked, through the delivery gate
+    fn simulate_delivery(
+        env: &Env,
+        config: &Config,
+        shadow: &mut Shadow,
+        series_id: &String,
+    ) -> Result<(), Error> {
+        if shadow.delivered.contains_key(series_id.clone()) {
+            return Err(ContractError::AlreadyDelivered.into());
+        }
+        let metadata = Self::delivery_status(env, series_id)?;
+        let buyer = metadata.buyer.clone().ok_or(ContractError::MissingBuyer)?;
+        let locked_amount = Self::locked_in_series(env, series_id, &buyer);
+        if locked_amount == 0 {
+            return Err(ContractError::NoLockedTokens.into());
+        }
+        shadow.delivered.set(series_id.clone(), true);
+
+        if let Some(escrow) = Self::agent_escrow(env.clone(), series_id.clone()) {
+            shadow.credit(env, &escrow.agent, series_id, escrow.amount);
+        }
+        let accrued = Self::compute_late_penalty(env, &metadata, locked_amount);
+        let penalty = match config.max_total_supply {
+            0 => accrued,
+            cap => accrued.min((cap - shadow.total_supply).max(0)),
+        };
+        shadow.total_supply = checked_add(env, shadow.total_supply, penalty);
+        let mut fee = 0;
+        if let Some((operator, operator_fee)) = Self::delivery_fee(env, &metadata, locked_amount) {
+            fee = operator_fee;
+            shadow.credit(env, &operator, series_id, fee);
+        }
+        // Deliveries awaiting acceptance do not reach the balance yet
+        if !metadata.buyer_acceptance_required {
+            let recipient = Self::resolve_token_recipient(env, series_id, &buyer);
+            shadow.credit(
+                env,
+                &recipient,
+                series_id,
+                checked_add(env, locked_amount - fee, penalty),
+            );
+        }
+        Ok(())
+    }
+
+    /// The simulator's name for the code the real call would fail with
+    fn sim_result(error: Error) -> SimResult {
+        let code = error.get_code();
+        match code {
+            c if c == ContractError::InsufficientBalance as u32 => SimResult::InsufficientBalance,
+            c if c == ContractError::SeriesNotFound as u32 => SimResult::SeriesNotFound,
+            c if c == ContractError::NotFutureContract as u32 => SimResult::NotFutureContract,
+            c if c == ContractError::NoLockedTokens as u32 => SimResult::NoLockedTokens,
+            c if c == ContractError::AlreadyDelivered as u32 => SimResult::AlreadyDelivered,
+            c if c == ContractError::SeriesDefaulted as u32 => SimResult::SeriesDefaulted,
+            c if c == ExtError::SeriesDisputed as u32 => SimResult::SeriesDisputed,
+            c if c == ExtError::SeriesCancelled as u32 => SimResult::SeriesCancelled,
+            _ => SimResult::Rejected(code),
+        }
+    }
+}
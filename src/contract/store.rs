@@ -0,0 +1,186 @@
+use super::{Config, ContangoToken, DataKey, ExtKey};
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val, unwrap::UnwrapOptimized};
+
+/// A key `Store` knows where to keep
+pub(super) trait StoreKey: IntoVal<Env, Val> {
+    fn is_persistent(&self) -> bool;
+}
+
+impl StoreKey for DataKey {
+    /// Holder, series and facility data lives in persistent storage, one entry per key;
+    /// contract-wide settings stay in the instance entry, which every call loads in full
+    fn is_persistent(&self) -> bool {
+        match self {
+            DataKey::Balance(_)
+            | DataKey::LockedBalance(_)
+            | DataKey::SeriesBalance(..)
+            | DataKey::HeldSeries(_)
+            | DataKey::Allowance(_)
+            | DataKey::FeesPaid(_)
+            | DataKey::ProducerQuota(_)
+            | DataKey::ProducerRecord(_)
+            | DataKey::AgentPending(_)
+            | DataKey::AssistedBurn(..)
+            | DataKey::SeriesMetadata(_)
+            | DataKey::PendingDelivery(_)
+            | DataKey::SeriesAllowlist(_)
+            | DataKey::SeriesStamps(_)
+            | DataKey::SeriesMinter(_)
+            | DataKey::SeriesStatus(_)
+            | DataKey::AgentEscrow(_)
+            | DataKey::InventoryAttestation(_)
+            | DataKey::SeriesSupply(_)
+            | DataKey::TokenRecipient(_)
+            | DataKey::SeriesLocked(_)
+            | DataKey::SeriesByIndex(_)
+            | DataKey::SeriesMinted(_)
+            | DataKey::PaymentEscrow(_)
+            | DataKey::OpLog(_)
+            | DataKey::Frozen(_)
+            | DataKey::FeeExempt(_)
+            | DataKey::Role(..)
+            | DataKey::Facility(_)
+            | DataKey::FacilityNames
+            | DataKey::FacilityInactive(_)
+            | DataKey::FacilityValidator(..)
+            | DataKey::ConfirmationWindow(_) => true,
+            DataKey::Config
+            | DataKey::State
+            | DataKey::RewardsHook
+            | DataKey::IssuanceCap
+            | DataKey::PendingIssuanceCap
+            | DataKey::IssuanceEpoch
+            | DataKey::PendingSupplyCap
+            | DataKey::OpSeq
+            | DataKey::SwapsDisabled
+            | DataKey::InsuranceBuffer
+            | DataKey::SeriesCount
+            | DataKey::BurnBreakdown
+            | DataKey::PriceOracle(..)
+            | DataKey::PendingAdmin
+            | DataKey::Paused
+            | DataKey::PendingMintingShutdown
+            | DataKey::MintingDisabled => false,
+        }
+    }
+}
+
+impl StoreKey for ExtKey {
+    /// `Busy` is transient and never goes through the store
+    fn is_persistent(&self) -> bool {
+        true
+    }
+}
+
+/// Contract storage, routed by key
+pub(super) struct Store<'a>(&'a Env);
+
+impl Store<'_> {
+    pub(super) fn get<V: TryFromVal<Env, Val>>(&self, key: &impl StoreKey) -> Option<V> {
+        let val = self.get_val(key.into_val(self.0), key.is_persistent())?;
+        Some(V::try_from_val(self.0, &val).unwrap_optimized())
+    }
+
+    pub(super) fn has(&self, key: &impl StoreKey) -> bool {
+        let persistent = key.is_persistent();
+        let key: Val = key.into_val(self.0);
+        let instance = self.0.storage().instance();
+        if persistent {
+            self.0.storage().persistent().has(&key) || instance.has(&key)
+        } else {
+            instance.has(&key)
+        }
+    }
+
+    pub(super) fn set<V: IntoVal<Env, Val>>(&self, key: &impl StoreKey, val: &V) {
+        self.set_val(
+            key.into_val(self.0),
+            key.is_persistent(),
+            val.into_val(self.0),
+        );
+    }
+
+    pub(super) fn remove(&self, key: &impl StoreKey) {
+        let persistent = key.is_persistent();
+        let key: Val = key.into_val(self.0);
+        if persistent {
+            self.0.storage().persistent().remove(&key);
+        }
+        self.0.storage().instance().remove(&key);
+    }
+
+    /// Move an entry left in the instance by an older deployment to where it now lives
+    #[cfg(feature = "maintenance")]
+    pub(super) fn relocate(&self, key: &impl StoreKey) {
+        let persistent = key.is_persistent();
+        let key: Val = key.into_val(self.0);
+        let instance = self.0.storage().instance();
+        if persistent && instance.has(&key) {
+            let val: Val = instance.get(&key).unwrap();
+            self.0.storage().persistent().set(&key, &val);
+            self.extend(key);
+            instance.remove(&key);
+        }
+    }
+
+    /// Extend a persistent entry's TTL, if the entry exists
+    #[cfg(feature = "maintenance")]
+    pub(super) fn bump(&self, key: &impl StoreKey) {
+        let persistent = key.is_persistent();
+        let key: Val = key.into_val(self.0);
+        if persistent && self.0.storage().persistent().has(&key) {
+            self.extend(key);
+        }
+    }
+
+    // The routing works on the key as a `Val`, so it is compiled once rather than per key and
+    // value type
+
+    fn get_val(&self, key: Val, persistent: bool) -> Option<Val> {
+        let instance = self.0.storage().instance();
+        if !persistent {
+            return instance.get(&key);
+        }
+        match self.0.storage().persistent().get(&key) {
+            Some(val) => {
+                self.extend(key);
+                Some(val)
+            }
+            // Deployments from before the move keep older entries in the instance until rewritten
+            None => instance.get(&key),
+        }
+    }
+
+    fn set_val(&self, key: Val, persistent: bool, val: Val) {
+        let instance = self.0.storage().instance();
+        if !persistent {
+            return instance.set(&key, &val);
+        }
+        self.0.storage().persistent().set(&key, &val);
+        self.extend(key);
+        if instance.has(&key) {
+            instance.remove(&key);
+        }
+    }
+
+    /// Every access to a persistent entry tops its TTL up per the configured policy, along with
+    /// the instance's, which holds the config every entry depends on
+    fn extend(&self, key: Val) {
+        let instance = self.0.storage().instance();
+        let config: Option<Config> = instance.get(&DataKey::Config);
+        if let Some(config) = config {
+            self.0.storage().persistent().extend_ttl(
+                &key,
+                config.ttl_threshold,
+                config.ttl_extend_to,
+            );
+            instance.extend_ttl(config.ttl_threshold, config.ttl_extend_to);
+        }
+    }
+}
+
+impl ContangoToken {
+    pub(super) fn store(env: &Env) -> Store<'_> {
+        Store(env)
+    }
+}
@@ -0,0 +1,281 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, ExtError,
+    SettingChange, checked_add, checked_add_u64,
+};
+use soroban_sdk::{Env, Symbol, contractimpl, contracttype, panic_with_error};
+
+/// Delay before a supply cap raise can take effect
+pub const SUPPLY_CAP_DELAY_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Delay before a scheduled issuance cap change can take effect
+pub const ISSUANCE_CAP_DELAY_SECS: u64 = 2 * 24 * 60 * 60;
+
+/// Delay before a scheduled minting shutdown can be made permanent
+pub const MINTING_SHUTDOWN_DELAY_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingCap {
+    pub cap: i128, // 0 = uncapped
+    pub eta: u64,  // Earliest timestamp the change can be applied
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct IssuanceEpoch {
+    pub start: u64, // First second of the calendar month (UTC)
+    pub minted: i128,
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Schedule permanently disabling minting, returns the eta (admin only, timelocked)
+    pub fn schedule_minting_shutdown(env: Env) -> u64 {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if !Self::minting_enabled(env.clone()) {
+            panic_with_error!(env, ExtError::MintingDisabled);
+        }
+        if Self::store(&env).has(&DataKey::PendingMintingShutdown) {
+            panic_with_error!(env, ContractError::AlreadyPending);
+        }
+
+        let eta = checked_add_u64(&env, env.ledger().timestamp(), MINTING_SHUTDOWN_DELAY_SECS);
+        Self::store(&env).set(&DataKey::PendingMintingShutdown, &eta);
+        env.events()
+            .publish((Symbol::new(&env, "minting_shutdown_scheduled"),), eta);
+
+        eta
+    }
+
+    /// Drop a scheduled minting shutdown before it is made permanent (admin only)
+    pub fn cancel_minting_shutdown(env: Env) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if !Self::store(&env).has(&DataKey::PendingMintingShutdown) {
+            panic_with_error!(env, ContractError::NothingPending);
+        }
+        Self::store(&env).remove(&DataKey::PendingMintingShutdown);
+        env.events()
+            .publish((Symbol::new(&env, "minting_shutdown_canceled"),), ());
+    }
+
+    /// Disable every mint path for good once the scheduled shutdown's timelock has
+    /// elapsed (admin only). Transfers, burns and deliveries keep working.
+    pub fn disable_minting_forever(env: Env) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        let eta: u64 = Self::store(&env)
+            .get(&DataKey::PendingMintingShutdown)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NothingPending));
+        if env.ledger().timestamp() < eta {
+            panic_with_error!(env, ContractError::TimelockNotExpired);
+        }
+
+        Self::store(&env).set(&DataKey::MintingDisabled, &true);
+        Self::store(&env).remove(&DataKey::PendingMintingShutdown);
+        env.events()
+            .publish((Symbol::new(&env, "minting_disabled_forever"),), ());
+    }
+
+    /// Check whether new tokens can still be minted
+    pub fn minting_enabled(env: Env) -> bool {
+        !Self::store(&env).has(&DataKey::MintingDisabled)
+    }
+
+    /// Schedule a new monthly issuance cap, 0 to lift it (admin only, timelocked)
+    pub fn schedule_issuance_cap(env: Env, cap: i128) -> SettingChange {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if cap < 0 {
+            panic_with_error!(env, ContractError::InvalidCap);
+        }
+
+        let eta = checked_add_u64(&env, env.ledger().timestamp(), ISSUANCE_CAP_DELAY_SECS);
+        Self::store(&env).set(&DataKey::PendingIssuanceCap, &PendingCap { cap, eta });
+
+        env.events()
+            .publish((Symbol::new(&env, "issuance_cap_scheduled"),), (cap, eta));
+
+        SettingChange {
+            effective: false,
+            effective_at: eta,
+            value: cap,
+        }
+    }
+
+    /// Apply the scheduled issuance cap once its timelock has elapsed (anyone)
+    pub fn apply_issuance_cap(env: Env) -> SettingChange {
+        Self::ensure_initialized(&env);
+        let pending: PendingCap = Self::store(&env)
+            .get(&DataKey::PendingIssuanceCap)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NothingPending));
+
+        if env.ledger().timestamp() < pending.eta {
+            panic_with_error!(env, ContractError::TimelockNotExpired);
+        }
+
+        Self::store(&env).set(&DataKey::IssuanceCap, &pending.cap);
+        Self::store(&env).remove(&DataKey::PendingIssuanceCap);
+
+        env.events()
+            .publish((Symbol::new(&env, "issuance_cap_applied"),), pending.cap);
+
+        Self::setting_applied(&env, pending.cap)
+    }
+
+    /// Schedule raising the total supply cap, 0 to lift it (admin only, timelocked)
+    pub fn raise_supply_cap(env: Env, new_cap: i128) -> SettingChange {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        if config.max_total_supply == 0 {
+            panic_with_error!(env, ContractError::InvalidCap);
+        }
+        if new_cap != 0 && new_cap <= config.max_total_supply {
+            panic_with_error!(env, ContractError::InvalidCap);
+        }
+
+        let eta = checked_add_u64(&env, env.ledger().timestamp(), SUPPLY_CAP_DELAY_SECS);
+        Self::store(&env).set(
+            &DataKey::PendingSupplyCap,
+            &PendingCap { cap: new_cap, eta },
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "supply_cap_raise_scheduled"),),
+            (config.max_total_supply, new_cap, eta),
+        );
+
+        SettingChange {
+            effective: false,
+            effective_at: eta,
+            value: new_cap,
+        }
+    }
+
+    /// Apply the scheduled supply cap raise once its timelock has elapsed (anyone)
+    pub fn apply_supply_cap(env: Env) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        let pending: PendingCap = Self::store(&env)
+            .get(&DataKey::PendingSupplyCap)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NothingPending));
+
+        if env.ledger().timestamp() < pending.eta {
+            panic_with_error!(env, ContractError::TimelockNotExpired);
+        }
+
+        let old_cap = config.max_total_supply;
+        config.max_total_supply = pending.cap;
+        Self::store(&env).set(&DataKey::Config, &config);
+        Self::store(&env).remove(&DataKey::PendingSupplyCap);
+
+        env.events().publish(
+            (Symbol::new(&env, "supply_cap_raised"),),
+            (old_cap, pending.cap),
+        );
+
+        Self::setting_applied(&env, pending.cap)
+    }
+
+    /// Get the supply cap raise awaiting its timelock, if any
+    pub fn pending_supply_cap(env: Env) -> Option<PendingCap> {
+        Self::store(&env).get(&DataKey::PendingSupplyCap)
+    }
+
+    /// Get the active monthly issuance cap, 0 = uncapped
+    pub fn monthly_issuance_cap(env: Env) -> i128 {
+        Self::get_issuance_cap(&env)
+    }
+
+    /// Get the issuance cap change awaiting its timelock, if any
+    pub fn pending_issuance_cap(env: Env) -> Option<PendingCap> {
+        Self::store(&env).get(&DataKey::PendingIssuanceCap)
+    }
+
+    /// Get how much can still be minted in the current epoch, None if uncapped
+    pub fn issuance_remaining(env: Env) -> Option<i128> {
+        let cap = Self::get_issuance_cap(&env);
+        if cap == 0 {
+            return None;
+        }
+        let minted = Self::get_issuance_epoch(&env).minted;
+        Some((cap - minted).max(0))
+    }
+
+    /// Get the (start, end) timestamps of the current issuance epoch
+    pub fn issuance_epoch(env: Env) -> (u64, u64) {
+        month_bounds(env.ledger().timestamp())
+    }
+}
+
+impl ContangoToken {
+    pub(super) fn get_issuance_cap(env: &Env) -> i128 {
+        Self::store(env).get(&DataKey::IssuanceCap).unwrap_or(0)
+    }
+
+    /// Issuance accounting for the current epoch, reset when the month rolls
+    pub(super) fn get_issuance_epoch(env: &Env) -> IssuanceEpoch {
+        let (start, _) = month_bounds(env.ledger().timestamp());
+        Self::store(env)
+            .get::<IssuanceEpoch>(&DataKey::IssuanceEpoch)
+            .filter(|epoch| epoch.start == start)
+            .unwrap_or(IssuanceEpoch { start, minted: 0 })
+    }
+
+    pub(super) fn require_issuance_headroom(env: &Env, amount: i128) {
+        let cap = Self::get_issuance_cap(env);
+        if cap > 0 && checked_add(env, Self::get_issuance_epoch(env).minted, amount) > cap {
+            panic_with_error!(env, ContractError::IssuanceCapExceeded);
+        }
+    }
+
+    /// Count a mint against the monthly issuance cap
+    pub(super) fn consume_issuance(env: &Env, amount: i128) {
+        let mut epoch = Self::get_issuance_epoch(env);
+        epoch.minted = checked_add(env, epoch.minted, amount);
+
+        Self::store(env).set(&DataKey::IssuanceEpoch, &epoch);
+    }
+}
+
+/// Start and end timestamps of the UTC calendar month containing `timestamp`
+fn month_bounds(timestamp: u64) -> (u64, u64) {
+    let (year, month) = civil_from_days(timestamp / 86400);
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    (
+        days_from_civil(year, month) * 86400,
+        days_from_civil(next_year, next_month) * 86400,
+    )
+}
+
+/// Year and month of a day count since the Unix epoch (proleptic Gregorian)
+fn civil_from_days(days: u64) -> (u64, u64) {
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as u64;
+    (year, month)
+}
+
+/// Day count since the Unix epoch of the first day of a month
+fn days_from_civil(year: u64, month: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
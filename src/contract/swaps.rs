@@ -0,0 +1,322 @@
+use super::{
+    ContangoToken, ContangoTokenArgs, ContangoTokenClient, ContractError, DataKey, ExtError, Role,
+    SeriesMetadata, SeriesStatus, SettingChange, checked_add, checked_bps, checked_sub,
+};
+use crate::config::Config;
+use crate::oracle::{Asset, PriceFeedClient};
+use soroban_sdk::{
+    Address, Env, Error, String, Symbol, contractimpl, contracttype, panic_with_error,
+};
+
+/// Furthest an admin swap price may stray from the feed's last quote (10%)
+pub const MAX_PRICE_OVERRIDE_BPS: i128 = 1000;
+
+/// Data of `swap` events, topics `("swap", from_series, to_series)`.
+/// `amount_in` was burned from the source series and `amount_out` minted in the destination.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapEvent {
+    pub holder: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+    pub oracle_price: i128, // Basis points of destination per source token
+}
+
+#[contractimpl]
+impl ContangoToken {
+    /// Convert tokens between series of the same asset at the configured oracle's rate;
+    /// fails if the output would fall below `min_amount_out`
+    pub fn swap(
+        env: Env,
+        from: Address,
+        from_series: String,
+        to_series: String,
+        amount: i128,
+        min_amount_out: i128,
+    ) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        Self::require_non_negative(&env, min_amount_out);
+        from.require_auth();
+
+        Self::do_swap(
+            &env,
+            from,
+            from_series,
+            to_series,
+            amount,
+            None,
+            min_amount_out,
+        );
+    }
+
+    /// Emergency swap at an admin-supplied price (bps) while the oracle's quote is stale
+    /// (admin and holder); the price must stay within `MAX_PRICE_OVERRIDE_BPS` of the last quote
+    pub fn swap_at_price(
+        env: Env,
+        from: Address,
+        from_series: String,
+        to_series: String,
+        amount: i128,
+        price: i128,
+        min_amount_out: i128,
+    ) {
+        Self::require_not_busy(&env);
+        Self::require_not_paused(&env);
+        let config = Self::load_config(&env);
+        Self::require_non_negative(&env, amount);
+        Self::require_non_negative(&env, min_amount_out);
+        if price <= 0 {
+            panic_with_error!(env, ContractError::InvalidParameter);
+        }
+        config.admin.require_auth();
+        from.require_auth();
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "swap_price_override"),
+                from_series.clone(),
+                to_series.clone(),
+            ),
+            price,
+        );
+        Self::do_swap(
+            &env,
+            from,
+            from_series,
+            to_series,
+            amount,
+            Some(price),
+            min_amount_out,
+        );
+    }
+
+    /// Set the SEP-40 feed pricing a currency pair, e.g. `BRL_USD`, for an asset type (admin only)
+    pub fn set_price_oracle(env: Env, asset_type: String, currency_pair: Symbol, oracle: Address) {
+        let config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        Self::store(&env).set(
+            &DataKey::PriceOracle(asset_type.clone(), currency_pair.clone()),
+            &oracle,
+        );
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "price_oracle_set"),
+                asset_type,
+                currency_pair,
+            ),
+            oracle,
+        );
+    }
+
+    /// Get the feed configured for an asset type and currency pair
+    pub fn price_oracle(env: Env, asset_type: String, currency_pair: Symbol) -> Option<Address> {
+        Self::store(&env).get(&DataKey::PriceOracle(asset_type, currency_pair))
+    }
+
+    /// Set how old an oracle price may be before swaps reject it (admin only)
+    pub fn set_oracle_max_age(env: Env, max_age_secs: u64) -> SettingChange {
+        let mut config = Self::load_config(&env);
+        config.admin.require_auth();
+
+        config.oracle_max_age_secs = max_age_secs;
+        Self::store(&env).set(&DataKey::Config, &config);
+
+        Self::setting_applied(&env, max_age_secs as i128)
+    }
+
+    /// Halt or resume swaps without affecting transfers or deliveries (Pauser role)
+    pub fn set_swaps_enabled(env: Env, caller: Address, enabled: bool) {
+        Self::load_config(&env);
+        Self::require_role(&env, Role::Pauser, &caller);
+
+        if enabled {
+            Self::store(&env).remove(&DataKey::SwapsDisabled);
+        } else {
+            Self::store(&env).set(&DataKey::SwapsDisabled, &true);
+        }
+
+        env.events()
+            .publish((Symbol::new(&env, "swaps_enabled"),), enabled);
+    }
+
+    /// Check whether swaps are currently accepted
+    pub fn swaps_enabled(env: Env) -> bool {
+        !Self::store(&env).has(&DataKey::SwapsDisabled)
+    }
+}
+
+impl ContangoToken {
+    /// Why a series can't be swapped out of (`source`) or into. Spendable tokens of a future
+    /// awaiting delivery may leave it, but a swap only mints into live spot or delivered series.
+    pub(super) fn swap_status(
+        env: &Env,
+        series_id: &String,
+        metadata: &SeriesMetadata,
+        source: bool,
+    ) -> Result<(), Error> {
+        match Self::get_series_status(env, series_id, metadata) {
+            SeriesStatus::Active | SeriesStatus::Delivered => Ok(()),
+            SeriesStatus::DeliveryPending if source => Ok(()),
+            SeriesStatus::DeliveryPending => Err(ExtError::SeriesLocked.into()),
+            SeriesStatus::Defaulted => Err(ContractError::SeriesDefaulted.into()),
+            SeriesStatus::Cancelled => Err(ExtError::SeriesCancelled.into()),
+            SeriesStatus::Disputed => Err(ExtError::SeriesDisputed.into()),
+        }
+    }
+
+    pub(super) fn do_swap(
+        env: &Env,
+        from: Address,
+        from_series: String,
+        to_series: String,
+        amount: i128,
+        override_price: Option<i128>,
+        min_amount_out: i128,
+    ) {
+        let config = Self::load_config(env);
+        if !Self::swaps_enabled(env.clone()) {
+            panic_with_error!(env, ContractError::SwapsDisabled);
+        }
+        // A swap mints into the destination series
+        Self::require_minting_enabled(env);
+        Self::require_not_frozen(env, &from);
+
+        // Get series metadata to validate swap compatibility
+        let from_metadata = Self::require_series(env, &from_series);
+        let to_metadata = Self::require_series(env, &to_series);
+        if let Err(reason) = Self::swap_status(env, &from_series, &from_metadata, true)
+            .and(Self::swap_status(env, &to_series, &to_metadata, false))
+        {
+            panic_with_error!(env, reason);
+        }
+        if Self::is_expired(env.clone(), from_series.clone()) {
+            panic_with_error!(env, ContractError::SeriesDefaulted);
+        }
+        if Self::get_series_balance(env, &from, &from_series) < amount {
+            panic_with_error!(env, ContractError::InsufficientBalance);
+        }
+        Self::require_fresh_backing(env, &from_series);
+        Self::require_fresh_backing(env, &to_series);
+
+        // Validate swap compatibility (same asset type)
+        if from_metadata.asset_type != to_metadata.asset_type {
+            panic_with_error!(env, ContractError::IncompatibleAssets);
+        }
+
+        if !Self::is_allowed_recipient(env, &to_series, &from) {
+            panic_with_error!(env, ContractError::RecipientNotAllowed);
+        }
+
+        // Price in basis points of destination per source token
+        let price = match override_price {
+            Some(price) => {
+                let (quote, _) = Self::oracle_quote(env, &from_metadata, &to_metadata);
+                let band = checked_bps(env, quote, MAX_PRICE_OVERRIDE_BPS);
+                if price.abs_diff(quote) > band as u128 {
+                    panic_with_error!(env, ExtError::PriceDeviation);
+                }
+                price
+            }
+            None => Self::oracle_price_bps(env, &config, &from_metadata, &to_metadata),
+        };
+        let swap_amount = checked_bps(env, amount, price);
+        if swap_amount < min_amount_out {
+            panic_with_error!(env, ContractError::SlippageExceeded);
+        }
+
+        // The destination can only absorb what its backed kg still covers
+        Self::require_series_headroom(env, &to_series, &to_metadata, swap_amount);
+
+        let mut state = Self::get_state(env);
+        let supply_after_burn = checked_sub(env, state.total_supply, amount);
+        Self::require_supply_headroom(env, &config, supply_after_burn, swap_amount);
+
+        // Execute swap by burning from one series and minting in another
+        Self::decrease_balance(env, &from, &from_series, amount);
+        Self::release_producer_quota(env, &from_series, amount);
+        Self::adjust_series_supply(env, &from_series, -amount);
+        Self::increase_balance(env, &from, &to_series, swap_amount);
+        Self::adjust_series_supply(env, &to_series, swap_amount);
+        Self::record_series_mint(env, &to_series, swap_amount);
+        state.total_supply = checked_add(env, supply_after_burn, swap_amount);
+        Self::put_state(env, &state);
+
+        Self::log_op(env, "swap", &from, Some(from_series.clone()), amount);
+
+        // Emit swap event
+        env.events().publish(
+            (Symbol::new(env, "swap"), from_series, to_series),
+            SwapEvent {
+                holder: from,
+                amount_in: amount,
+                amount_out: swap_amount,
+                oracle_price: price,
+            },
+        );
+    }
+
+    /// Fetch the pair's rate from its SEP-40 feed, rejecting missing or stale prices
+    pub(super) fn oracle_price_bps(
+        env: &Env,
+        config: &Config,
+        from_metadata: &SeriesMetadata,
+        to_metadata: &SeriesMetadata,
+    ) -> i128 {
+        let (price, timestamp) = Self::oracle_quote(env, from_metadata, to_metadata);
+        let age = env.ledger().timestamp().saturating_sub(timestamp);
+        if age > config.oracle_max_age_secs {
+            panic_with_error!(env, ContractError::OraclePriceStale);
+        }
+        price
+    }
+
+    /// The pair's last quote in bps and when it was published, however old
+    pub(super) fn oracle_quote(
+        env: &Env,
+        from_metadata: &SeriesMetadata,
+        to_metadata: &SeriesMetadata,
+    ) -> (i128, u64) {
+        let pair = Self::currency_pair(env, &from_metadata.currency, &to_metadata.currency);
+        let oracle: Address = Self::store(env)
+            .get(&DataKey::PriceOracle(
+                from_metadata.asset_type.clone(),
+                pair.clone(),
+            ))
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::OracleNotConfigured));
+
+        let feed = PriceFeedClient::new(env, &oracle);
+        let (data, decimals) = Self::call_out(env, || {
+            (feed.lastprice(&Asset::Other(pair)), feed.decimals())
+        });
+        let data =
+            data.unwrap_or_else(|| panic_with_error!(env, ContractError::OraclePriceUnavailable));
+        if data.price <= 0 {
+            panic_with_error!(env, ContractError::OraclePriceUnavailable);
+        }
+
+        match (data.price.checked_mul(10000), 10i128.checked_pow(decimals)) {
+            (Some(scaled), Some(scale)) => (scaled / scale, data.timestamp),
+            _ => panic_with_error!(env, ContractError::ArithmeticOverflow),
+        }
+    }
+
+    /// Pair symbol `FROM_TO` for two currency codes, e.g. `BRL_USD`
+    pub(super) fn currency_pair(env: &Env, from: &String, to: &String) -> Symbol {
+        let mut buf = [0u8; 32];
+        let (from_len, to_len) = (from.len() as usize, to.len() as usize);
+        if from_len + to_len + 1 > buf.len() {
+            panic_with_error!(env, ContractError::InvalidCurrencyPair);
+        }
+        from.copy_into_slice(&mut buf[..from_len]);
+        buf[from_len] = b'_';
+        to.copy_into_slice(&mut buf[from_len + 1..from_len + 1 + to_len]);
+        let pair = core::str::from_utf8(&buf[..from_len + 1 + to_len])
+            .unwrap_or_else(|_| panic_with_error!(env, ContractError::InvalidCurrencyPair));
+        Symbol::new(env, pair)
+    }
+}
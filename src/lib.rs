@@ -2,9 +2,13 @@
 
 mod config;
 mod contract;
+#[cfg(feature = "swaps")]
+mod oracle;
 mod storage_types;
 
 #[cfg(test)]
 mod test;
 
-pub use crate::contract::ContangoTokenClient;
+pub use crate::contract::{ContangoTokenClient, SERIES_TRANSFER_TOPICS, TRANSFER_TOPICS};
+#[cfg(feature = "swaps")]
+pub use crate::oracle::{Asset, PriceData, PriceFeed};
@@ -0,0 +1,23 @@
+use soroban_sdk::{Address, Env, Symbol, contractclient, contracttype};
+
+/// Asset identifier of a SEP-40 price feed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128, // Scaled by the feed's `decimals`
+    pub timestamp: u64,
+}
+
+/// The subset of the SEP-40 (Reflector) interface swaps rely on
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+    fn decimals(env: Env) -> u32;
+}
@@ -1,8 +1,90 @@
 #[cfg(test)]
 mod comprehensive_tests {
-    use crate::contract::{ContangoToken, ContangoTokenClient, Distribution, SeriesMetadata};
+    #[cfg(feature = "insights")]
+    use crate::contract::BurnKind;
+    #[cfg(feature = "maintenance")]
+    use crate::contract::DAY_IN_LEDGERS;
+    #[cfg(all(
+        feature = "batch",
+        feature = "insights",
+        any(feature = "facility-ops", feature = "producers", feature = "recovery")
+    ))]
+    use crate::contract::DeliveryEvent;
+    #[cfg(all(feature = "payments", feature = "producers"))]
+    use crate::contract::ExtKey;
+    #[cfg(feature = "facility-ops")]
+    use crate::contract::FeeShare;
+    #[cfg(any(
+        all(feature = "insights", feature = "maintenance"),
+        all(feature = "series-admin", feature = "swaps")
+    ))]
+    use crate::contract::Role;
+    #[cfg(feature = "series-admin")]
+    use crate::contract::SeriesMetadataV1;
+    #[cfg(any(
+        feature = "batch",
+        feature = "defaults",
+        feature = "insights",
+        all(feature = "payments", feature = "producers")
+    ))]
+    use crate::contract::SeriesStatus;
+    #[cfg(feature = "supply-controls")]
+    use crate::contract::SettingChange;
+    #[cfg(feature = "batch")]
+    use crate::contract::batch::{MAX_MINT_BATCH, MAX_TRANSFER_BATCH, MintEntry};
+    #[cfg(feature = "payments")]
+    use crate::contract::escrow::PaymentEscrow;
+    #[cfg(feature = "facility-ops")]
+    use crate::contract::facility_ops::ConfirmationWindow;
+    #[cfg(feature = "insights")]
+    use crate::contract::insights::{
+        Approval, FacilityView, FootprintHint, OpKind, SeriesPosition,
+    };
+    #[cfg(feature = "maintenance")]
+    use crate::contract::maintenance::MAX_MIGRATION_BATCH;
+    #[cfg(feature = "series-admin")]
+    use crate::contract::series_admin::MetadataUpdate;
+    #[cfg(feature = "insights")]
+    use crate::contract::simulate::{SimOp, SimResult};
+    #[cfg(feature = "supply-controls")]
+    use crate::contract::supply::MINTING_SHUTDOWN_DELAY_SECS;
+    #[cfg(feature = "swaps")]
+    use crate::contract::swaps::SwapEvent;
+    use crate::contract::{
+        BurnEvent, ContangoToken, ContangoTokenClient, ContractError, DataKey, Distribution,
+        ExtError, LotInfo, MintEvent, ReasonCode, SeriesLot, SeriesMetadata, SeriesRecord,
+        TransferAmounts,
+    };
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    use crate::contract::{MAX_REASON_NOTE_LEN, Reason};
+    #[cfg(feature = "batch")]
+    use crate::contract::{SERIES_TRANSFER_TOPICS, TRANSFER_TOPICS};
+    #[cfg(all(feature = "insights", feature = "maintenance"))]
+    use crate::contract::{TokenState, TokenStateV1};
+    #[cfg(feature = "swaps")]
+    use crate::oracle::{Asset, PriceData};
+    #[cfg(feature = "maintenance")]
+    use crate::storage_types::AllowanceDataKey;
+    #[cfg(any(
+        feature = "series-admin",
+        all(feature = "insights", feature = "maintenance")
+    ))]
+    use soroban_sdk::Map;
+    #[cfg(all(feature = "batch", feature = "insights", feature = "producers"))]
+    use soroban_sdk::testutils::Logs;
     use soroban_sdk::testutils::arbitrary::std::println;
-    use soroban_sdk::{Address, Env, String, testutils::Address as _};
+    #[cfg(feature = "maintenance")]
+    use soroban_sdk::testutils::storage::{Instance as _, Persistent as _};
+    #[cfg(feature = "payments")]
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::{
+        Address, Bytes, BytesN, Env, Error, IntoVal, String, Symbol, TryFromVal, Val,
+        testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+        token::TokenClient,
+        vec,
+    };
+    #[cfg(feature = "swaps")]
+    use soroban_sdk::{contract, contractimpl};
 
     fn setup_test_env() -> (Env, ContangoTokenClient<'static>, TestAddresses) {
         let env = Env::default();
@@ -23,11 +105,24 @@ mod comprehensive_tests {
             &String::from_str(&env, "CTG"),
             &addresses.admin,
             &addresses.storage,
+            &String::from_str(&env, "contango.com.br"),
+            &0,
         );
 
         (env, client, addresses)
     }
 
+    fn assert_contract_error<T, E: core::fmt::Debug>(
+        result: Result<T, Result<Error, E>>,
+        expected: impl Into<Error> + core::fmt::Debug + Copy,
+    ) {
+        match result {
+            Err(Ok(error)) => assert_eq!(error, expected.into()),
+            Err(Err(error)) => panic!("unexpected invoke error {:?}", error),
+            Ok(_) => panic!("call succeeded, expected {:?}", expected),
+        }
+    }
+
     struct TestAddresses {
         admin: Address,
         storage: Address,
@@ -41,7 +136,7 @@ mod comprehensive_tests {
     fn test_initialization_parameters() {
         let (env, client, addresses) = setup_test_env();
 
-        let config = client.get_config();
+        let config = client.get_config().unwrap();
         assert_eq!(config.name, String::from_str(&env, "Contango Token"));
         assert_eq!(config.symbol, String::from_str(&env, "CTG"));
         assert_eq!(config.admin, addresses.admin);
@@ -54,15 +149,19 @@ mod comprehensive_tests {
 
     // Test 2: Cannot reinitialize
     #[test]
-    #[should_panic(expected = "Contract already initialized")]
     fn test_cannot_reinitialize() {
         let (env, client, addresses) = setup_test_env();
 
-        client.initialize(
-            &String::from_str(&env, "Another Token"),
-            &String::from_str(&env, "ATK"),
-            &addresses.admin,
-            &addresses.storage,
+        assert_contract_error(
+            client.try_initialize(
+                &String::from_str(&env, "Another Token"),
+                &String::from_str(&env, "ATK"),
+                &addresses.admin,
+                &addresses.storage,
+                &String::from_str(&env, "contango.com.br"),
+                &0,
+            ),
+            ContractError::AlreadyInitialized,
         );
     }
 
@@ -82,6 +181,7 @@ mod comprehensive_tests {
         };
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata,
             &distribution,
@@ -97,7 +197,6 @@ mod comprehensive_tests {
 
     // Test 4: Invalid distribution percentages
     #[test]
-    #[should_panic(expected = "Distribution percentages must sum to 100%")]
     fn test_invalid_distribution_percentages() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
@@ -111,11 +210,15 @@ mod comprehensive_tests {
             storage_percent: 50,
         };
 
-        client.mint_spot(
-            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
-            &metadata,
-            &distribution,
-            &1_000_000,
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &metadata,
+                &distribution,
+                &1_000_000,
+            ),
+            ContractError::InvalidDistribution,
         );
     }
 
@@ -129,10 +232,12 @@ mod comprehensive_tests {
 
         // Step 1: Mint future tokens
         client.mint_future(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-USD-2025Q4"),
             &metadata,
             &addresses.buyer,
-            &addresses.guarantee_agent,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
             &500_000,
         );
 
@@ -140,7 +245,11 @@ mod comprehensive_tests {
         assert_eq!(client.locked_balance_of(&addresses.buyer), 495_000); // 99%
         assert_eq!(client.balance_of(&addresses.buyer), 0);
         assert_eq!(client.balance_of(&addresses.admin), 2_500); // 0.5%
-        assert_eq!(client.balance_of(&addresses.guarantee_agent), 2_500); // 0.5%
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 0);
+        assert_eq!(
+            client.agent_pending_earnings(&addresses.guarantee_agent),
+            2_500
+        ); // 0.5%, held until delivery
 
         // Step 2: Confirm delivery
         client.confirm_delivery(
@@ -151,11 +260,12 @@ mod comprehensive_tests {
         // Verify unlocked tokens
         assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
         assert_eq!(client.balance_of(&addresses.buyer), 495_000);
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 2_500);
+        assert_eq!(client.agent_pending_earnings(&addresses.guarantee_agent), 0);
     }
 
     // Test 6: Cannot confirm delivery for spot contract
     #[test]
-    #[should_panic(expected = "Not a future contract")]
     fn test_cannot_confirm_delivery_spot() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
@@ -164,15 +274,19 @@ mod comprehensive_tests {
         let distribution = create_standard_distribution(&addresses);
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata,
             &distribution,
             &1_000_000,
         );
 
-        client.confirm_delivery(
-            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
-            &addresses.storage,
+        assert_contract_error(
+            client.try_confirm_delivery(
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &addresses.storage,
+            ),
+            ContractError::NotFutureContract,
         );
     }
 
@@ -191,8 +305,7 @@ mod comprehensive_tests {
         client.transfer(
             &addresses.producer,
             &addresses.third_party,
-            &100_000,
-            &false, // no fee
+            &100_000, // no transfer fee configured
         );
 
         assert_eq!(
@@ -210,7 +323,7 @@ mod comprehensive_tests {
         env.mock_all_auths();
 
         // Set transfer fee to 1%
-        client.set_transfer_fee(&100);
+        client.set_transfer_fee(&addresses.admin, &100);
 
         // Setup initial balance
         mint_spot_tokens(&env, &client, &addresses, 1_000_000);
@@ -221,8 +334,7 @@ mod comprehensive_tests {
         client.transfer(
             &addresses.producer,
             &addresses.third_party,
-            &100_000,
-            &true, // apply fee
+            &100_000, // fee follows contract policy
         );
 
         assert_eq!(client.balance_of(&addresses.producer), 890_000); // 990k - 100k
@@ -243,7 +355,7 @@ mod comprehensive_tests {
         let initial_storage = client.balance_of(&addresses.storage);
 
         // Burn 100k tokens
-        client.burn(
+        client.burn_series(
             &addresses.producer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &100_000,
@@ -258,16 +370,13 @@ mod comprehensive_tests {
 
     // Test 10: Insufficient balance operations
     #[test]
-    #[should_panic(expected = "Insufficient balance")]
     fn test_insufficient_balance_transfer() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
 
-        client.transfer(
-            &addresses.producer,
-            &addresses.third_party,
-            &100_000,
-            &false,
+        assert_contract_error(
+            client.try_transfer(&addresses.producer, &addresses.third_party, &100_000),
+            ContractError::InsufficientBalance,
         );
     }
 
@@ -281,7 +390,13 @@ mod comprehensive_tests {
         let distribution = create_standard_distribution(&addresses);
         let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
 
-        client.mint_spot(&series_id, &metadata, &distribution, &1_000_000);
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &1_000_000,
+        );
 
         let retrieved = client.get_series(&series_id).unwrap();
         assert_eq!(retrieved.asset_type, metadata.asset_type);
@@ -291,6 +406,7 @@ mod comprehensive_tests {
     }
 
     // Test 12: Swap functionality
+    #[cfg(feature = "swaps")]
     #[test]
     fn test_swap_same_asset_type() {
         let (env, client, addresses) = setup_test_env();
@@ -305,6 +421,7 @@ mod comprehensive_tests {
 
         // Mint both series
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata_brl,
             &distribution,
@@ -312,23 +429,36 @@ mod comprehensive_tests {
         );
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-USD-2025Q1"),
             &metadata_usd,
             &distribution,
-            &0, // Just create the series
+            &500_000, // Half of the destination's backed kg stays available
         );
 
         // Perform swap (oracle price 5500 = 0.55 BRL/USD)
+        set_fx_price(&env, &client, 5500);
         client.swap(
             &addresses.producer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &String::from_str(&env, "CTGSoy-USD-2025Q1"),
             &100_000,
-            &5500,
+            &0,
         );
 
         // Verify swap executed
-        assert_eq!(client.balance_of(&addresses.producer), 890_000 + 55_000);
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            890_000 + 495_000 + 55_000
+        );
+        assert_eq!(
+            client.series_supply(&String::from_str(&env, "CTGSoy-BRL-2025Q1")),
+            900_000
+        );
+        assert_eq!(
+            client.series_supply(&String::from_str(&env, "CTGSoy-USD-2025Q1")),
+            555_000
+        );
     }
 
     // Test 13: Admin-only functions
@@ -339,17 +469,19 @@ mod comprehensive_tests {
         env.mock_all_auths_allowing_non_root_auth();
 
         addresses.producer.require_auth();
-        client.set_transfer_fee(&200);
+        client.set_transfer_fee(&addresses.admin, &200);
     }
 
     // Test 14: Maximum fee limits
     #[test]
-    #[should_panic(expected = "Fee too high")]
     fn test_maximum_fee_limit() {
-        let (env, client, _addresses) = setup_test_env();
+        let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
 
-        client.set_transfer_fee(&600); // 6% - too high
+        assert_contract_error(
+            client.try_set_transfer_fee(&addresses.admin, &600),
+            ContractError::FeeTooHigh,
+        ); // 6% - too high
     }
 
     // Helper functions
@@ -367,6 +499,11 @@ mod comprehensive_tests {
             contract_hash: String::from_str(env, "0x123456789abcdef"),
             is_future: false,
             guarantee_agent: None,
+            buyer_acceptance_required: false,
+            late_penalty_bps_per_day: 0,
+            late_penalty_cap_bps: 0,
+            metadata_uri: None,
+            lot: SeriesLot::Bulk,
         }
     }
 
@@ -384,6 +521,11 @@ mod comprehensive_tests {
             contract_hash: String::from_str(env, "0xfedcba9876543210"),
             is_future: true,
             guarantee_agent: Some(addresses.guarantee_agent.clone()),
+            buyer_acceptance_required: false,
+            late_penalty_bps_per_day: 0,
+            late_penalty_cap_bps: 0,
+            metadata_uri: None,
+            lot: SeriesLot::Bulk,
         }
     }
 
@@ -407,6 +549,7 @@ mod comprehensive_tests {
         let distribution = create_standard_distribution(addresses);
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(env, "CTGSoy-BRL-2025Q1"),
             &metadata,
             &distribution,
@@ -425,12 +568,12 @@ mod comprehensive_tests {
         assert_eq!(client.balance_of(&addresses.producer), 990_000);
 
         // Step 2: Producer sells 200k tokens to buyer
-        client.transfer(&addresses.producer, &addresses.buyer, &200_000, &false);
+        client.transfer(&addresses.producer, &addresses.buyer, &200_000);
         assert_eq!(client.balance_of(&addresses.producer), 790_000);
         assert_eq!(client.balance_of(&addresses.buyer), 200_000);
 
         // Step 3: Buyer burns 50k tokens for physical delivery
-        client.burn(
+        client.burn_series(
             &addresses.buyer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &50_000,
@@ -447,7 +590,6 @@ mod comprehensive_tests {
 
     // Test 16: Future contract default scenario
     #[test]
-    #[should_panic(expected = "No locked tokens for this buyer")]
     fn test_future_contract_no_locked_tokens() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
@@ -456,10 +598,12 @@ mod comprehensive_tests {
 
         // Mint future tokens
         client.mint_future(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-USD-2025Q4"),
             &metadata,
             &addresses.buyer,
-            &addresses.guarantee_agent,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
             &500_000,
         );
 
@@ -469,15 +613,18 @@ mod comprehensive_tests {
             &addresses.storage,
         );
 
-        client.confirm_delivery(
-            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
-            &addresses.storage,
+        assert_contract_error(
+            client.try_confirm_delivery(
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &addresses.storage,
+            ),
+            ContractError::AlreadyDelivered,
         );
     }
 
     // Test 17: Swap between incompatible assets
+    #[cfg(feature = "swaps")]
     #[test]
-    #[should_panic(expected = "Can only swap between same asset types")]
     fn test_swap_incompatible_assets() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
@@ -487,6 +634,7 @@ mod comprehensive_tests {
         let distribution = create_standard_distribution(&addresses);
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata_soy,
             &distribution,
@@ -498,6 +646,7 @@ mod comprehensive_tests {
         metadata_corn.asset_type = String::from_str(&env, "corn");
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGCorn-BRL-2025Q1"),
             &metadata_corn,
             &distribution,
@@ -505,12 +654,15 @@ mod comprehensive_tests {
         );
 
         // Try to swap between different asset types
-        client.swap(
-            &addresses.producer,
-            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
-            &String::from_str(&env, "CTGCorn-BRL-2025Q1"),
-            &100_000,
-            &10000,
+        assert_contract_error(
+            client.try_swap(
+                &addresses.producer,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &String::from_str(&env, "CTGCorn-BRL-2025Q1"),
+                &100_000,
+                &0,
+            ),
+            ContractError::IncompatibleAssets,
         );
     }
 
@@ -523,10 +675,10 @@ mod comprehensive_tests {
         mint_spot_tokens(&env, &client, &addresses, 1_000_000);
 
         // Zero transfer should work
-        client.transfer(&addresses.producer, &addresses.buyer, &0, &false);
+        client.transfer(&addresses.producer, &addresses.buyer, &0);
 
         // Zero burn should work
-        client.burn(
+        client.burn_series(
             &addresses.producer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &0,
@@ -539,7 +691,6 @@ mod comprehensive_tests {
 
     // Test 19: Metadata validation for future contracts
     #[test]
-    #[should_panic(expected = "Metadata must indicate future contract")]
     fn test_future_mint_requires_future_flag() {
         let (env, client, addresses) = setup_test_env();
         env.mock_all_auths();
@@ -547,12 +698,17 @@ mod comprehensive_tests {
         let mut metadata = create_spot_metadata(&env, &addresses.producer);
         metadata.is_future = false; // This should cause panic
 
-        client.mint_future(
-            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
-            &metadata,
-            &addresses.buyer,
-            &addresses.guarantee_agent,
-            &500_000,
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &500_000,
+            ),
+            ContractError::NotFutureContract,
         );
     }
 
@@ -571,6 +727,7 @@ mod comprehensive_tests {
 
         // Mint from multiple series
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata1,
             &distribution,
@@ -578,6 +735,7 @@ mod comprehensive_tests {
         );
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q2"),
             &metadata2,
             &distribution,
@@ -591,10 +749,10 @@ mod comprehensive_tests {
         assert_eq!(client.total_supply(), 1_000_000);
 
         // Transfer some tokens
-        client.transfer(&addresses.producer, &addresses.buyer, &100_000, &false);
+        client.transfer(&addresses.producer, &addresses.buyer, &100_000);
 
         // Burn some tokens from different party
-        client.burn(
+        client.burn_series(
             &addresses.buyer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &50_000,
@@ -615,10 +773,12 @@ mod comprehensive_tests {
         env.mock_all_auths();
 
         // Mint large amount
-        let metadata = create_spot_metadata(&env, &addresses.producer);
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 100_000_000;
         let distribution = create_standard_distribution(&addresses);
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata,
             &distribution,
@@ -631,7 +791,7 @@ mod comprehensive_tests {
 
         // Multiple small transfers
         for _i in 0..10 {
-            client.transfer(&addresses.producer, &addresses.buyer, &1_000_000, &false);
+            client.transfer(&addresses.producer, &addresses.buyer, &1_000_000);
         }
 
         assert_eq!(client.balance_of(&addresses.producer), 89_000_000);
@@ -652,6 +812,7 @@ mod comprehensive_tests {
         let distribution = create_standard_distribution(&addresses);
 
         client.mint_spot(
+            &addresses.admin,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &metadata,
             &distribution,
@@ -665,7 +826,7 @@ mod comprehensive_tests {
 
         // 2. Producer sells 300k tokens to trader
         let trader = Address::generate(&env);
-        client.transfer(&addresses.producer, &trader, &300_000, &false);
+        client.transfer(&addresses.producer, &trader, &300_000);
 
         println!(
             "Trader balance after purchase: {}",
@@ -673,14 +834,13 @@ mod comprehensive_tests {
         );
 
         // 3. Enable transfer fees for secondary market
-        client.set_transfer_fee(&50); // 0.5%
+        client.set_transfer_fee(&addresses.admin, &50); // 0.5%
 
         // 4. Trader sells to end buyer with fee
         client.transfer(
             &trader,
             &addresses.buyer,
-            &300_000,
-            &true, // Apply fee
+            &300_000, // Secondary-market transfer pays the fee
         );
 
         println!(
@@ -693,7 +853,7 @@ mod comprehensive_tests {
         );
 
         // 5. End buyer burns tokens for physical delivery
-        client.burn(
+        client.burn_series(
             &addresses.buyer,
             &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
             &100_000,
@@ -711,4 +871,7362 @@ mod comprehensive_tests {
         assert_eq!(client.balance_of(&addresses.admin), 6_750);
         assert_eq!(client.balance_of(&addresses.storage), 5_250);
     }
+
+    fn mint_acceptance_future(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let mut metadata = create_future_metadata(env, addresses);
+        metadata.buyer_acceptance_required = true;
+
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(env, "CTGSoy-USD-2025Q4"),
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+    }
+
+    // Test 21: Buyer acceptance completes the unlock
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_buyer_acceptance_unlocks_pending_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_acceptance_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        // Tokens leave the lock but are not spendable until the buyer accepts
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+        assert_eq!(client.balance_of(&addresses.buyer), 0);
+        let pending = client.pending_delivery(&series_id).unwrap();
+        assert_eq!(pending.buyer, addresses.buyer);
+        assert_eq!(pending.amount, 495_000);
+
+        client.accept_delivery(&series_id);
+
+        assert_eq!(client.balance_of(&addresses.buyer), 495_000);
+        assert!(client.pending_delivery(&series_id).is_none());
+    }
+
+    // Test 22: Validator confirmation auto-finalizes after the acceptance window
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_delivery_auto_finalizes_after_timeout() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_acceptance_timeout(&3_600);
+        mint_acceptance_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1_000);
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        env.ledger().set_timestamp(1_000 + 3_600);
+        client.pause(&addresses.admin, &ReasonCode::OperationalError, &None);
+        assert_contract_error(
+            client.try_finalize_delivery(&series_id),
+            ContractError::ContractPaused,
+        );
+        client.unpause(&addresses.admin);
+        client.finalize_delivery(&series_id);
+
+        assert_eq!(client.balance_of(&addresses.buyer), 495_000);
+        assert!(client.pending_delivery(&series_id).is_none());
+    }
+
+    // Test 23: Finalization is rejected while the buyer can still accept
+    #[test]
+    fn test_cannot_finalize_before_timeout() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_acceptance_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1_000);
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        env.ledger().set_timestamp(1_000 + 60);
+        assert_contract_error(
+            client.try_finalize_delivery(&series_id),
+            ContractError::TimelockNotExpired,
+        );
+    }
+
+    // Test 24: Buyer cannot accept a delivery the validator has not confirmed
+    #[test]
+    fn test_cannot_accept_unconfirmed_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_acceptance_future(&env, &client, &addresses);
+
+        assert_contract_error(
+            client.try_accept_delivery(&String::from_str(&env, "CTGSoy-USD-2025Q4")),
+            ContractError::NothingPending,
+        );
+    }
+
+    // Test 25: Zero-fee transfers emit the same event whether or not a fee was requested
+    #[test]
+    fn test_zero_fee_transfer_paths_are_identical() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        client.transfer(&addresses.producer, &addresses.third_party, &100_000);
+        let with_fee_flag = env.events().all();
+        let balance_after_first = client.balance_of(&addresses.third_party);
+
+        client.transfer(&addresses.producer, &addresses.third_party, &100_000);
+        let without_fee_flag = env.events().all();
+
+        assert_eq!(with_fee_flag, without_fee_flag);
+        assert_eq!(
+            without_fee_flag,
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (
+                        Symbol::new(&env, "transfer_amounts"),
+                        addresses.producer.clone(),
+                        addresses.third_party.clone()
+                    )
+                        .into_val(&env),
+                    TransferAmounts {
+                        gross: 100_000,
+                        fee: 0,
+                        net: 100_000,
+                        fee_recipient: None,
+                    }
+                    .into_val(&env),
+                ),
+                (
+                    client.address.clone(),
+                    (
+                        Symbol::new(&env, "transfer"),
+                        addresses.producer.clone(),
+                        addresses.third_party.clone()
+                    )
+                        .into_val(&env),
+                    100_000i128.into_val(&env),
+                ),
+            ]
+        );
+        assert_eq!(balance_after_first, 100_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 200_000);
+        assert_eq!(client.balance_of(&addresses.admin), 5_000);
+    }
+
+    // Test 26: Fee-bearing transfers report the fee alongside the net amount
+    #[test]
+    fn test_fee_transfer_event_reports_fee() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_transfer_fee(&addresses.admin, &100);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        client.transfer(&addresses.producer, &addresses.third_party, &100_000);
+
+        let (_, data) = last_event(&env, "transfer_amounts");
+        let amounts: TransferAmounts = data.into_val(&env);
+        assert_eq!(amounts.gross, 100_000);
+        assert_eq!(amounts.net, 99_000);
+        assert_eq!(amounts.fee, 1_000);
+        assert_eq!(amounts.fee_recipient, Some(addresses.admin.clone()));
+
+        // SEP-41 consumers see the fee as its own transfer to the platform
+        let (topics, data) = last_event(&env, "transfer");
+        let expected = (
+            Symbol::new(&env, "transfer"),
+            addresses.producer.clone(),
+            addresses.admin.clone(),
+        )
+            .into_val(&env);
+        assert_eq!(topics, expected);
+        assert_eq!(i128::try_from_val(&env, &data).unwrap(), 1_000);
+        assert_eq!(client.balance_of(&addresses.producer), 890_000);
+    }
+
+    fn mint_penalty_future(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let mut metadata = create_future_metadata(env, addresses);
+        metadata.late_penalty_bps_per_day = 10; // 0.1% per day
+        metadata.late_penalty_cap_bps = 200; // 2% maximum
+
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(env, "CTGSoy-USD-2025Q4"),
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+    }
+
+    // Test 27: On-time delivery carries no penalty
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_on_time_delivery_has_no_penalty() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_penalty_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1751328000); // Exactly on the delivery date
+        assert_eq!(client.accrued_penalty(&series_id), 0);
+
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        assert_eq!(client.balance_of(&addresses.buyer), 495_000);
+        assert_eq!(client.total_supply(), 500_000);
+    }
+
+    // Test 28: Late delivery mints the accrued penalty to the buyer
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_late_delivery_pays_penalty() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_penalty_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1751328000 + 10 * 86_400); // 10 days late
+        assert_eq!(client.accrued_penalty(&series_id), 4_950); // 1% of 495k
+
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        assert_eq!(client.balance_of(&addresses.buyer), 499_950);
+        assert_eq!(client.total_supply(), 504_950);
+        assert_eq!(client.accrued_penalty(&series_id), 0);
+    }
+
+    // Test 29: Penalty stops growing at the series cap
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_late_penalty_is_capped() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_penalty_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1751328000 + 90 * 86_400); // 90 days late
+        assert_eq!(client.accrued_penalty(&series_id), 9_900); // Capped at 2%
+
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        assert_eq!(client.balance_of(&addresses.buyer), 504_900);
+    }
+
+    // Test 30: Position statement gathers every section for one address, page by page
+    #[cfg(all(feature = "insights", feature = "recovery"))]
+    #[test]
+    fn test_statement_of_address() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let producer = &addresses.producer;
+        let spot_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let future_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &future_id,
+            &create_future_metadata(&env, &addresses),
+            producer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        // A delivered future held until the buyer accepts
+        let mut accepted = create_future_metadata(&env, &addresses);
+        accepted.id = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        accepted.buyer_acceptance_required = true;
+        client.mint_future(
+            &addresses.admin,
+            &accepted.id,
+            &accepted,
+            producer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &10_000,
+        );
+        client.confirm_delivery(&accepted.id, &addresses.storage);
+
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+        client.transfer(producer, &addresses.buyer, &100_000);
+        client.burn_series(producer, &spot_id, &100_000);
+
+        let spender = Address::generate(&env);
+        client.approve(producer, &spender, &5_000, &1_000);
+        client.approve(&addresses.third_party, producer, &7_000, &2_000);
+        client.set_facility_operator(&String::from_str(&env, "AGRARIA"), &addresses.storage);
+        client.assisted_burn(
+            &spot_id,
+            producer,
+            &300_000,
+            &String::from_str(&env, "0xabad1dea"),
+        );
+        client.freeze_account(producer, &ReasonCode::Sanctions, &None);
+
+        let statement = client.statement_of(producer, &0, &10);
+        assert_eq!(statement.owner, *producer);
+        assert_eq!(statement.balance, 790_000);
+        assert_eq!(statement.locked_balance, 99_000);
+        assert_eq!(statement.fees_paid, 1_000 + 500); // Transfer fee + burn fee
+        assert!(statement.frozen);
+        assert_eq!(statement.series_count, 3);
+        assert_eq!(
+            statement.series,
+            vec![
+                &env,
+                SeriesPosition {
+                    series_id: spot_id.clone(),
+                    free: 790_000,
+                    locked: 0,
+                    awaiting_acceptance: 0,
+                    pending_burn: 300_000,
+                },
+                SeriesPosition {
+                    series_id: future_id.clone(),
+                    free: 0,
+                    locked: 99_000,
+                    awaiting_acceptance: 0,
+                    pending_burn: 0,
+                },
+                SeriesPosition {
+                    series_id: accepted.id.clone(),
+                    free: 0,
+                    locked: 0,
+                    awaiting_acceptance: 9_900,
+                    pending_burn: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            statement.approvals_granted,
+            vec![
+                &env,
+                Approval {
+                    counterparty: spender.clone(),
+                    amount: 5_000,
+                    expiration_ledger: 1_000,
+                }
+            ]
+        );
+        assert_eq!(
+            statement.approvals_received,
+            vec![
+                &env,
+                Approval {
+                    counterparty: addresses.third_party.clone(),
+                    amount: 7_000,
+                    expiration_ledger: 2_000,
+                }
+            ]
+        );
+
+        // Pages slice the lists; the totals repeat on every page
+        let second = client.statement_of(producer, &1, &1);
+        assert_eq!(second.balance, 790_000);
+        assert_eq!(second.series.len(), 1);
+        assert_eq!(second.series.get(0).unwrap().series_id, future_id);
+        assert!(second.approvals_granted.is_empty());
+        assert!(second.approvals_received.is_empty());
+        assert!(client.statement_of(producer, &3, &1).series.is_empty());
+
+        // Accepted deliveries join the spendable holdings; revoked approvals drop out
+        client.unfreeze_account(producer);
+        client.accept_delivery(&accepted.id);
+        client.approve(producer, &spender, &0, &0);
+        let statement = client.statement_of(producer, &0, &10);
+        assert_eq!(statement.series_count, 3);
+        let delivered = statement.series.get(1).unwrap();
+        assert_eq!(delivered.series_id, accepted.id);
+        assert_eq!(delivered.free, 9_900);
+        assert_eq!(delivered.awaiting_acceptance, 0);
+        assert!(statement.approvals_granted.is_empty());
+
+        let empty = client.statement_of(&addresses.third_party, &0, &10);
+        assert_eq!(empty.balance, 0);
+        assert_eq!(empty.locked_balance, 0);
+        assert_eq!(empty.fees_paid, 0);
+        assert!(!empty.frozen);
+        assert_eq!(empty.series_count, 0);
+        assert_eq!(empty.approvals_granted.len(), 1);
+    }
+
+    // Test 31: Token metadata view agrees with the standard entry points
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_token_metadata_view() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        let metadata = client.token_metadata().unwrap();
+        assert_eq!(metadata.name, String::from_str(&env, "Contango Token"));
+        assert_eq!(metadata.symbol, String::from_str(&env, "CTG"));
+        assert_eq!(metadata.decimals, 0);
+        assert_eq!(
+            metadata.home_domain,
+            String::from_str(&env, "contango.com.br")
+        );
+        assert_eq!(metadata.series_count, 1);
+
+        assert_eq!(client.name(), metadata.name);
+        assert_eq!(client.symbol(), metadata.symbol);
+        assert_eq!(client.decimals(), metadata.decimals);
+    }
+
+    // Test 32: Admin can change the advertised home domain
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_set_home_domain() {
+        let (env, client, _addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_home_domain(&String::from_str(&env, "tokens.contango.com.br"));
+
+        assert_eq!(
+            client.token_metadata().unwrap().home_domain,
+            String::from_str(&env, "tokens.contango.com.br")
+        );
+        assert_eq!(
+            client.get_config().unwrap().home_domain,
+            String::from_str(&env, "tokens.contango.com.br")
+        );
+    }
+
+    #[cfg(feature = "swaps")]
+    fn mint_swap_series(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let metadata_brl = create_spot_metadata(env, &addresses.producer);
+        let mut metadata_usd = metadata_brl.clone();
+        metadata_usd.currency = String::from_str(env, "USD");
+        let distribution = create_standard_distribution(addresses);
+
+        client.mint_spot(
+            &addresses.admin,
+            &String::from_str(env, "CTGSoy-BRL-2025Q1"),
+            &metadata_brl,
+            &distribution,
+            &1_000_000,
+        );
+        client.mint_spot(
+            &addresses.admin,
+            &String::from_str(env, "CTGSoy-USD-2025Q1"),
+            &metadata_usd,
+            &distribution,
+            &500_000,
+        );
+        set_fx_price(env, client, 5500); // 0.55 BRL/USD
+    }
+
+    #[cfg(feature = "swaps")]
+    #[contract]
+    pub struct MockPriceFeed;
+
+    #[cfg(feature = "swaps")]
+    #[contractimpl]
+    impl MockPriceFeed {
+        pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) {
+            env.storage()
+                .instance()
+                .set(&asset, &PriceData { price, timestamp });
+        }
+
+        pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+            env.storage().instance().get(&asset)
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            14
+        }
+    }
+
+    /// Point soy BRL_USD swaps at a fresh mock feed quoting `price_bps`
+    #[cfg(feature = "swaps")]
+    fn set_fx_price(env: &Env, client: &ContangoTokenClient, price_bps: i128) -> Address {
+        let oracle = env.register(MockPriceFeed, ());
+        MockPriceFeedClient::new(env, &oracle).set_price(
+            &Asset::Other(Symbol::new(env, "BRL_USD")),
+            &(price_bps * 10i128.pow(10)),
+            &env.ledger().timestamp(),
+        );
+        client.set_price_oracle(
+            &String::from_str(env, "soy"),
+            &Symbol::new(env, "BRL_USD"),
+            &oracle,
+        );
+        oracle
+    }
+
+    // Test 33: Closed series rejects outsiders until they are added
+    #[cfg(all(feature = "allowlists", feature = "swaps"))]
+    #[test]
+    fn test_series_recipient_allowlist() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+
+        // Open series accept anyone
+        assert!(client.is_series_recipient(&usd, &addresses.producer));
+
+        client.add_series_recipient(&usd, &addresses.buyer);
+        assert!(client.is_series_recipient(&usd, &addresses.buyer));
+        assert!(!client.is_series_recipient(&usd, &addresses.producer));
+
+        let rejected = client.try_swap(&addresses.producer, &brl, &usd, &100_000, &0);
+        assert!(rejected.is_err());
+
+        client.add_series_recipient(&usd, &addresses.producer);
+        client.swap(&addresses.producer, &brl, &usd, &100_000, &0);
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            890_000 + 495_000 + 55_000
+        );
+        assert_eq!(
+            client.series_supply(&String::from_str(&env, "CTGSoy-BRL-2025Q1")),
+            900_000
+        );
+        assert_eq!(
+            client.series_supply(&String::from_str(&env, "CTGSoy-USD-2025Q1")),
+            555_000
+        );
+
+        client.remove_series_recipient(&usd, &addresses.producer);
+        assert!(!client.is_series_recipient(&usd, &addresses.producer));
+    }
+
+    // Test 34: Producer-managed allowlists require the producer's signature
+    #[cfg(all(feature = "allowlists", feature = "swaps"))]
+    #[test]
+    fn test_producer_managed_allowlist() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+
+        client.set_series_allowlist_manager(&usd, &true);
+        client.add_series_recipient(&usd, &addresses.buyer);
+
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert_eq!(auths[0].0, addresses.producer);
+    }
+
+    // Test 35: Allowlist changes are rejected for unknown series
+    #[cfg(feature = "allowlists")]
+    #[test]
+    fn test_allowlist_requires_existing_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        assert_contract_error(
+            client
+                .try_add_series_recipient(&String::from_str(&env, "CTG-UNKNOWN"), &addresses.buyer),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    fn has_event(env: &Env, name: &str) -> bool {
+        let name = Symbol::new(env, name);
+        env.events().all().iter().any(|(_, topics, _)| {
+            topics
+                .get(0)
+                .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+                == Some(name.clone())
+        })
+    }
+
+    /// Topics and data of the latest event called `name`
+    fn last_event(env: &Env, name: &str) -> (soroban_sdk::Vec<Val>, Val) {
+        let name = Symbol::new(env, name);
+        env.events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| {
+                topics
+                    .get(0)
+                    .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+                    == Some(name.clone())
+            })
+            .map(|(_, topics, data)| (topics, data))
+            .last()
+            .expect("event not published")
+    }
+
+    fn mint_facility_series(
+        env: &Env,
+        client: &ContangoTokenClient,
+        addresses: &TestAddresses,
+        series_id: &str,
+        quantity_kg: u64,
+    ) {
+        let mut metadata = create_spot_metadata(env, &addresses.producer);
+        metadata.id = String::from_str(env, series_id);
+        metadata.quantity_kg = quantity_kg;
+
+        client.mint_spot(
+            &addresses.admin,
+            &String::from_str(env, series_id),
+            &metadata,
+            &create_standard_distribution(addresses),
+            &(quantity_kg as i128),
+        );
+    }
+
+    // Test 36: Facility utilization warning, over-capacity rejection and custody transfer
+    #[cfg(all(feature = "insights", feature = "series-admin"))]
+    #[test]
+    fn test_facility_capacity_registry() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let agraria = String::from_str(&env, "AGRARIA");
+        let slc = String::from_str(&env, "SLC");
+        client.set_facility_capacity(&agraria, &1_000_000);
+
+        // Fill AGRARIA to 95% and get the warning
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 950_000);
+        assert!(has_event(&env, "capacity_warning"));
+        assert_eq!(client.get_facility(&agraria).unwrap().backed_kg, 950_000);
+
+        // A further 100t series would overfill the facility
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 100_000;
+        let over = client.try_mint_spot(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-BRL-2025Q2"),
+            &metadata,
+            &create_standard_distribution(&addresses),
+            &100_000,
+        );
+        assert!(over.is_err());
+
+        // Moving custody to SLC frees AGRARIA
+        client.transfer_custody(&String::from_str(&env, "CTGSoy-BRL-2025Q1"), &slc);
+        assert_eq!(client.get_facility(&agraria).unwrap().backed_kg, 0);
+        assert_eq!(client.get_facility(&slc).unwrap().backed_kg, 950_000);
+        assert_eq!(
+            client
+                .get_series(&String::from_str(&env, "CTGSoy-BRL-2025Q1"))
+                .unwrap()
+                .storage_facility,
+            slc
+        );
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 100_000);
+        assert!(!has_event(&env, "capacity_warning"));
+        assert_eq!(client.get_facility(&agraria).unwrap().backed_kg, 100_000);
+    }
+
+    // Test 37: Custody cannot move into a facility without room
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_custody_transfer_respects_capacity() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_facility_capacity(&String::from_str(&env, "SLC"), &500_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 950_000);
+
+        assert_contract_error(
+            client.try_transfer_custody(
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &String::from_str(&env, "SLC"),
+            ),
+            ContractError::FacilityCapacityExceeded,
+        );
+    }
+
+    #[cfg(feature = "rewards")]
+    mod rewards_hooks {
+        pub mod recording {
+            use soroban_sdk::{Address, Env, String, contract, contractimpl, symbol_short};
+
+            /// Records every notification it receives
+            #[contract]
+            pub struct MockRewards;
+
+            #[contractimpl]
+            impl MockRewards {
+                pub fn notify_transfer(
+                    env: Env,
+                    from: Address,
+                    to: Address,
+                    amount: i128,
+                    _series: Option<String>,
+                ) {
+                    let calls: u32 = env
+                        .storage()
+                        .instance()
+                        .get(&symbol_short!("calls"))
+                        .unwrap_or(0);
+                    env.storage()
+                        .instance()
+                        .set(&symbol_short!("calls"), &(calls + 1));
+                    env.storage()
+                        .instance()
+                        .set(&symbol_short!("last"), &(from, to, amount));
+                }
+
+                pub fn calls(env: Env) -> u32 {
+                    env.storage()
+                        .instance()
+                        .get(&symbol_short!("calls"))
+                        .unwrap_or(0)
+                }
+
+                pub fn last(env: Env) -> (Address, Address, i128) {
+                    env.storage()
+                        .instance()
+                        .get(&symbol_short!("last"))
+                        .unwrap()
+                }
+            }
+        }
+
+        pub mod panicking {
+            use soroban_sdk::{Address, Env, String, contract, contractimpl};
+
+            /// Always traps
+            #[contract]
+            pub struct PanickingRewards;
+
+            #[contractimpl]
+            impl PanickingRewards {
+                pub fn notify_transfer(
+                    _env: Env,
+                    _from: Address,
+                    _to: Address,
+                    _amount: i128,
+                    _series: Option<String>,
+                ) {
+                    panic!("rewards program offline");
+                }
+            }
+        }
+
+        pub mod reentrant {
+            use soroban_sdk::{Address, Env, String, contract, contractimpl, symbol_short};
+
+            use crate::contract::ContangoTokenClient;
+
+            /// Tries to move the sender's tokens again from inside the notification
+            #[contract]
+            pub struct ReentrantRewards;
+
+            #[contractimpl]
+            impl ReentrantRewards {
+                pub fn set_token(env: Env, token: Address) {
+                    env.storage()
+                        .instance()
+                        .set(&symbol_short!("token"), &token);
+                }
+
+                pub fn notify_transfer(
+                    env: Env,
+                    from: Address,
+                    to: Address,
+                    amount: i128,
+                    _series: Option<String>,
+                ) {
+                    let token: Address = env
+                        .storage()
+                        .instance()
+                        .get(&symbol_short!("token"))
+                        .unwrap();
+                    ContangoTokenClient::new(&env, &token).transfer(&from, &to, &amount);
+                }
+            }
+        }
+    }
+
+    // Test 38: Fee-bearing transfers notify the rewards contract
+    #[cfg(feature = "rewards")]
+    #[test]
+    fn test_rewards_hook_notified() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let hook_id = env.register(rewards_hooks::recording::MockRewards, ());
+        let hook = rewards_hooks::recording::MockRewardsClient::new(&env, &hook_id);
+        client.set_rewards_hook(&Some(hook_id.clone()));
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        // No fee configured yet, so nothing to report
+        client.transfer(&addresses.producer, &addresses.buyer, &10_000);
+        assert_eq!(hook.calls(), 0);
+
+        client.set_transfer_fee(&addresses.admin, &100);
+        client.transfer(&addresses.producer, &addresses.buyer, &10_000);
+        assert_eq!(hook.calls(), 1);
+        assert_eq!(
+            hook.last(),
+            (addresses.producer.clone(), addresses.buyer.clone(), 10_000)
+        );
+
+        client.set_rewards_hook(&None);
+        client.transfer(&addresses.producer, &addresses.buyer, &10_000);
+        assert_eq!(hook.calls(), 1);
+    }
+
+    // Test 39: A trapping rewards hook does not revert the transfer
+    #[cfg(feature = "rewards")]
+    #[test]
+    fn test_failing_rewards_hook_is_isolated() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let hook_id = env.register(rewards_hooks::panicking::PanickingRewards, ());
+        client.set_rewards_hook(&Some(hook_id));
+        client.set_transfer_fee(&addresses.admin, &100);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        client.transfer(&addresses.producer, &addresses.buyer, &100_000);
+
+        assert!(has_event(&env, "hook_failed"));
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+        assert_eq!(client.balance_of(&addresses.producer), 890_000);
+    }
+
+    // Test 40: A hook reentering transfer is rejected by the host
+    #[cfg(feature = "rewards")]
+    #[test]
+    fn test_reentrant_rewards_hook_is_rejected() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let hook_id = env.register(rewards_hooks::reentrant::ReentrantRewards, ());
+        rewards_hooks::reentrant::ReentrantRewardsClient::new(&env, &hook_id)
+            .set_token(&client.address);
+        client.set_rewards_hook(&Some(hook_id));
+        client.set_transfer_fee(&addresses.admin, &100);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        client.transfer(&addresses.producer, &addresses.buyer, &100_000);
+
+        // Only the outer transfer went through
+        assert!(has_event(&env, "hook_failed"));
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+        assert_eq!(client.balance_of(&addresses.producer), 890_000);
+    }
+
+    // Test 41: Burning against an unknown series is rejected
+    #[test]
+    fn test_burn_unknown_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        assert_contract_error(
+            client.try_burn_series(
+                &addresses.producer,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q9"),
+                &100_000,
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 42: Swapping out of an unknown series is rejected
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_from_unknown_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+
+        assert_contract_error(
+            client.try_swap(
+                &addresses.producer,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q9"),
+                &String::from_str(&env, "CTGSoy-USD-2025Q1"),
+                &100_000,
+                &0,
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 43: Swapping into an unknown series is rejected
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_to_unknown_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+
+        assert_contract_error(
+            client.try_swap(
+                &addresses.producer,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &String::from_str(&env, "CTGSoy-USD-2025Q9"),
+                &100_000,
+                &0,
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 44: Confirming delivery of an unknown series is rejected
+    #[test]
+    fn test_confirm_delivery_unknown_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        assert_contract_error(
+            client.try_confirm_delivery(
+                &String::from_str(&env, "CTGSoy-USD-2025Q9"),
+                &addresses.storage,
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 45: Custody of an unknown series cannot be transferred
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_transfer_custody_unknown_series() {
+        let (env, client, _addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        assert_contract_error(
+            client.try_transfer_custody(
+                &String::from_str(&env, "CTGSoy-BRL-2025Q9"),
+                &String::from_str(&env, "SLC"),
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 46: Simulation projects the same balances as real execution
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_simulation_matches_execution() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let ops = vec![
+            &env,
+            SimOp::Transfer(
+                addresses.producer.clone(),
+                addresses.third_party.clone(),
+                200_000,
+            ),
+            SimOp::Burn(addresses.third_party.clone(), spot.clone(), 50_000),
+            SimOp::ConfirmDelivery(future.clone()),
+            SimOp::Transfer(addresses.buyer.clone(), addresses.producer.clone(), 5_000),
+        ];
+
+        let supply_before = client.total_supply();
+        let producer_before = client.balance_of(&addresses.producer);
+        let report = client.simulate(&ops);
+
+        // Nothing was written
+        assert_eq!(client.total_supply(), supply_before);
+        assert_eq!(client.balance_of(&addresses.producer), producer_before);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 495_000);
+        assert_eq!(
+            report.results,
+            vec![
+                &env,
+                SimResult::Ok,
+                SimResult::Ok,
+                SimResult::Ok,
+                SimResult::Ok
+            ]
+        );
+
+        // Execute the same operations for real
+        client.transfer(&addresses.producer, &addresses.third_party, &200_000);
+        client.burn_series(&addresses.third_party, &spot, &50_000);
+        client.confirm_delivery(&future, &addresses.storage);
+        client.transfer(&addresses.buyer, &addresses.producer, &5_000);
+
+        for (addr, projected) in report.balances.iter() {
+            assert_eq!(client.balance_of(&addr), projected);
+        }
+        assert_eq!(report.balances.len(), 6);
+        assert_eq!(report.total_supply, client.total_supply());
+    }
+
+    // Test 47: Failing operations are reported without aborting the simulation
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_simulation_reports_failures() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+
+        let report = client.simulate(&vec![
+            &env,
+            SimOp::Transfer(addresses.buyer.clone(), addresses.producer.clone(), 1),
+            SimOp::Burn(
+                addresses.producer.clone(),
+                String::from_str(&env, "CTGSoy-BRL-2025Q9"),
+                1,
+            ),
+            SimOp::ConfirmDelivery(spot.clone()),
+            SimOp::Burn(addresses.producer.clone(), spot, 100_000),
+        ]);
+
+        assert_eq!(
+            report.results,
+            vec![
+                &env,
+                SimResult::InsufficientBalance,
+                SimResult::SeriesNotFound,
+                SimResult::NotFutureContract,
+                SimResult::Ok,
+            ]
+        );
+        assert_eq!(
+            report.balances.get(addresses.producer.clone()),
+            Some(890_000)
+        );
+    }
+
+    // Test 48: Simulations are bounded
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_simulation_is_bounded() {
+        let (env, client, addresses) = setup_test_env();
+
+        let mut ops = vec![&env];
+        for _ in 0..21 {
+            ops.push_back(SimOp::Transfer(
+                addresses.producer.clone(),
+                addresses.buyer.clone(),
+                1,
+            ));
+        }
+
+        assert_contract_error(client.try_simulate(&ops), ContractError::TooManyItems);
+    }
+
+    // Test 49: Issuance cap changes are timelocked
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_issuance_cap_timelock() {
+        let (env, client, _addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800); // 2025-03-10
+
+        assert_eq!(client.issuance_remaining(), None);
+        client.schedule_issuance_cap(&1_000_000);
+        assert_eq!(client.monthly_issuance_cap(), 0);
+
+        let pending = client.pending_issuance_cap().unwrap();
+        assert_eq!(pending.eta, 1741564800 + 2 * 24 * 60 * 60);
+        assert!(client.try_apply_issuance_cap().is_err());
+
+        env.ledger().set_timestamp(pending.eta);
+        client.apply_issuance_cap();
+        assert_eq!(client.monthly_issuance_cap(), 1_000_000);
+        assert_eq!(client.issuance_remaining(), Some(1_000_000));
+        assert!(client.pending_issuance_cap().is_none());
+    }
+
+    // Test 50: Mints accumulate against the monthly cap until the epoch rolls
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_issuance_cap_epochs() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800); // 2025-03-10
+        client.schedule_issuance_cap(&1_000_000);
+        env.ledger().set_timestamp(1741564800 + 2 * 24 * 60 * 60);
+        client.apply_issuance_cap();
+
+        // March 2025 epoch
+        assert_eq!(client.issuance_epoch(), (1740787200, 1743465600));
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 600_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 400_000);
+        assert_eq!(client.issuance_remaining(), Some(0));
+
+        let metadata = create_spot_metadata(&env, &addresses.producer);
+        let distribution = create_standard_distribution(&addresses);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q3");
+        assert!(
+            client
+                .try_mint_spot(&addresses.admin, &series_id, &metadata, &distribution, &1)
+                .is_err()
+        );
+
+        // April 2025 epoch starts with a fresh budget
+        env.ledger().set_timestamp(1743465600);
+        assert_eq!(client.issuance_remaining(), Some(1_000_000));
+        client.mint_spot(&addresses.admin, &series_id, &metadata, &distribution, &1);
+        assert_eq!(client.issuance_remaining(), Some(999_999));
+        assert_eq!(client.total_supply(), 1_000_001);
+    }
+
+    // Test 51: Epoch boundaries follow calendar months, including leap years
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_issuance_epoch_boundaries() {
+        let (env, client, _addresses) = setup_test_env();
+
+        env.ledger().set_timestamp(1708000000); // 2024-02-15
+        assert_eq!(client.issuance_epoch(), (1706745600, 1709251200));
+
+        env.ledger().set_timestamp(1735689599); // 2024-12-31 23:59:59
+        assert_eq!(client.issuance_epoch(), (1733011200, 1735689600));
+    }
+
+    mod smart_wallets {
+        pub mod approving {
+            use soroban_sdk::{Env, String, Symbol, contract, contractimpl};
+
+            /// Consents only to actions it has been told to approve
+            #[contract]
+            pub struct MockWallet;
+
+            #[contractimpl]
+            impl MockWallet {
+                pub fn approve(env: Env, action: Symbol) {
+                    env.storage().instance().set(&action, &true);
+                }
+
+                pub fn is_authorized(env: Env, action: Symbol, _series_id: String) -> bool {
+                    env.storage().instance().get(&action).unwrap_or(false)
+                }
+            }
+        }
+    }
+
+    fn mint_wallet_future(
+        env: &Env,
+        client: &ContangoTokenClient,
+        addresses: &TestAddresses,
+    ) -> Address {
+        let wallet = env.register(smart_wallets::approving::MockWallet, ());
+        let mut metadata = create_future_metadata(env, addresses);
+        metadata.buyer_acceptance_required = true;
+
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(env, "CTGSoy-USD-2025Q4"),
+            &metadata,
+            &wallet,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        client.confirm_delivery(
+            &String::from_str(env, "CTGSoy-USD-2025Q4"),
+            &addresses.storage,
+        );
+        wallet
+    }
+
+    // Test 52: A smart-wallet buyer consents through is_authorized instead of require_auth
+    #[test]
+    fn test_smart_wallet_buyer_accepts_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let wallet = mint_wallet_future(&env, &client, &addresses);
+        smart_wallets::approving::MockWalletClient::new(&env, &wallet)
+            .approve(&Symbol::new(&env, "accept_delivery"));
+
+        // No signatures are supplied for the acceptance itself
+        env.set_auths(&[]);
+        client.accept_delivery(&String::from_str(&env, "CTGSoy-USD-2025Q4"));
+
+        assert_eq!(client.balance_of(&wallet), 495_000);
+    }
+
+    // Test 53: A smart-wallet buyer that withholds consent blocks acceptance
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_smart_wallet_buyer_denies_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let wallet = mint_wallet_future(&env, &client, &addresses);
+        smart_wallets::approving::MockWalletClient::new(&env, &wallet)
+            .approve(&Symbol::new(&env, "raise_dispute"));
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        assert!(client.try_accept_delivery(&series_id).is_err());
+        assert_eq!(client.balance_of(&wallet), 0);
+        assert!(client.pending_delivery(&series_id).is_some());
+    }
+
+    // Test 54: Series record when they were created and last amended
+    #[cfg(all(feature = "insights", feature = "series-admin"))]
+    #[test]
+    fn test_series_creation_stamps() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(1_200);
+        env.ledger().set_timestamp(1741564800);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+
+        let info = client.series_info(&series_id).unwrap();
+        assert_eq!(info.metadata.quantity_kg, 1_000_000);
+        assert_eq!(info.stamps.created_ledger, 1_200);
+        assert_eq!(info.stamps.created_at, 1741564800);
+        assert_eq!(info.stamps.updated_at, 1741564800);
+
+        // Amending metadata moves only the update stamp
+        env.ledger().set_sequence_number(1_500);
+        env.ledger().set_timestamp(1741651200);
+        client.transfer_custody(&series_id, &String::from_str(&env, "SLC"));
+
+        let info = client.series_info(&series_id).unwrap();
+        assert_eq!(
+            info.metadata.storage_facility,
+            String::from_str(&env, "SLC")
+        );
+        assert_eq!(info.stamps.created_ledger, 1_200);
+        assert_eq!(info.stamps.created_at, 1741564800);
+        assert_eq!(info.stamps.updated_at, 1741651200);
+
+        assert!(
+            client
+                .series_info(&String::from_str(&env, "CTGSoy-BRL-2025Q9"))
+                .is_none()
+        );
+    }
+
+    #[cfg(feature = "recovery")]
+    fn propose_assisted_burn(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        mint_spot_tokens(env, client, addresses, 1_000_000);
+        client.set_facility_operator(&String::from_str(env, "AGRARIA"), &addresses.storage);
+        client.assisted_burn(
+            &String::from_str(env, "CTGSoy-BRL-2025Q1"),
+            &addresses.producer,
+            &300_000,
+            &String::from_str(env, "0xabad1dea"),
+        );
+    }
+
+    // Test 55: Assisted burns need admin and facility operator, then wait out the veto window
+    #[cfg(feature = "recovery")]
+    #[test]
+    fn test_assisted_burn_after_delay() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        propose_assisted_burn(&env, &client, &addresses);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 2);
+        assert_eq!(auths[0].0, addresses.admin);
+        assert_eq!(auths[1].0, addresses.storage);
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        assert!(
+            client
+                .try_execute_assisted_burn(&series_id, &addresses.producer)
+                .is_err()
+        );
+
+        env.ledger().set_timestamp(1741564800 + 7 * 24 * 60 * 60);
+        client.execute_assisted_burn(&series_id, &addresses.producer);
+
+        assert!(has_event(&env, "assisted_burn"));
+        assert_eq!(client.balance_of(&addresses.producer), 690_000);
+        assert_eq!(client.total_supply(), 700_000);
+        assert!(
+            client
+                .pending_assisted_burn(&series_id, &addresses.producer)
+                .is_none()
+        );
+    }
+
+    // Test 56: The holder can veto an assisted burn if they regain access
+    #[cfg(feature = "recovery")]
+    #[test]
+    fn test_assisted_burn_holder_veto() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        propose_assisted_burn(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        client.veto_assisted_burn(&series_id, &addresses.producer);
+
+        env.ledger().set_timestamp(7 * 24 * 60 * 60);
+        assert!(
+            client
+                .try_execute_assisted_burn(&series_id, &addresses.producer)
+                .is_err()
+        );
+        assert_eq!(client.balance_of(&addresses.producer), 990_000);
+    }
+
+    // Test 57: Facilities without an operator cannot co-sign assisted burns
+    #[cfg(feature = "recovery")]
+    #[test]
+    fn test_assisted_burn_requires_operator() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        assert_contract_error(
+            client.try_assisted_burn(
+                &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+                &addresses.producer,
+                &300_000,
+                &String::from_str(&env, "0xabad1dea"),
+            ),
+            ContractError::FacilityHasNoOperator,
+        );
+    }
+
+    // Test 58: Small futures may omit the guarantee agent, large ones may not
+    #[cfg(feature = "defaults")]
+    #[test]
+    fn test_guarantee_threshold() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let metadata = create_future_metadata(&env, &addresses);
+        let small = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let large = String::from_str(&env, "CTGSoy-USD-2026Q1");
+
+        // Default policy requires an agent for every future
+        assert!(
+            client
+                .try_mint_future(
+                    &addresses.admin,
+                    &small,
+                    &metadata,
+                    &addresses.buyer,
+                    &None,
+                    &None,
+                    &100_000
+                )
+                .is_err()
+        );
+
+        client.set_guarantee_policy(&100_000, &false);
+        client.mint_future(
+            &addresses.admin,
+            &small,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &None,
+            &100_000,
+        );
+
+        assert!(client.get_series(&small).unwrap().guarantee_agent.is_none());
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 99_000);
+        assert_eq!(client.balance_of(&addresses.admin), 1_000); // Platform takes both shares
+
+        assert!(
+            client
+                .try_mint_future(
+                    &addresses.admin,
+                    &large,
+                    &metadata,
+                    &addresses.buyer,
+                    &None,
+                    &None,
+                    &100_001
+                )
+                .is_err()
+        );
+        client.mint_future(
+            &addresses.admin,
+            &large,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &200_000,
+        );
+        assert_eq!(
+            client.get_series(&large).unwrap().guarantee_agent,
+            Some(addresses.guarantee_agent.clone())
+        );
+        assert_eq!(
+            client.agent_pending_earnings(&addresses.guarantee_agent),
+            1_000
+        );
+    }
+
+    // Test 59: The unguaranteed share can be routed to the buyer instead
+    #[cfg(feature = "defaults")]
+    #[test]
+    fn test_unguaranteed_share_to_buyer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_guarantee_policy(&100_000, &true);
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &None,
+            &100_000,
+        );
+
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 99_500);
+        assert_eq!(client.balance_of(&addresses.admin), 500);
+    }
+
+    #[cfg(feature = "producers")]
+    fn producer_mint(
+        env: &Env,
+        client: &ContangoTokenClient,
+        addresses: &TestAddresses,
+        series_id: &str,
+        amount: i128,
+    ) -> Result<(), ()> {
+        let mut metadata = create_spot_metadata(env, &addresses.producer);
+        metadata.quantity_kg = amount as u64;
+        client
+            .try_producer_mint_spot(
+                &addresses.producer,
+                &String::from_str(env, series_id),
+                &metadata,
+                &create_standard_distribution(addresses),
+                &amount,
+            )
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+
+    // Test 60: Producers self-mint their own series within quota, with the facility co-signing
+    #[cfg(feature = "producers")]
+    #[test]
+    fn test_producer_mint_within_quota() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_facility_operator(&String::from_str(&env, "AGRARIA"), &addresses.storage);
+        assert!(producer_mint(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1).is_err());
+
+        client.set_producer_quota(&addresses.producer, &500_000);
+        assert!(producer_mint(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 300_000).is_ok());
+
+        let auths = env.auths();
+        assert_eq!(auths.len(), 2);
+        assert_eq!(auths[0].0, addresses.producer);
+        assert_eq!(auths[1].0, addresses.storage);
+
+        assert_eq!(client.balance_of(&addresses.producer), 297_000);
+        assert_eq!(
+            client
+                .get_producer_quota(&addresses.producer)
+                .unwrap()
+                .outstanding,
+            300_000
+        );
+
+        // Exceeding the quota fails, burning frees it up again
+        assert!(producer_mint(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 200_001).is_err());
+        client.burn_series(
+            &addresses.producer,
+            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+            &100_000,
+        );
+        assert_eq!(
+            client
+                .get_producer_quota(&addresses.producer)
+                .unwrap()
+                .outstanding,
+            200_500
+        );
+        assert!(producer_mint(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 200_000).is_ok());
+    }
+
+    // Test 61: Producers cannot mint series belonging to another producer
+    #[cfg(feature = "producers")]
+    #[test]
+    fn test_producer_mint_rejects_foreign_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_facility_operator(&String::from_str(&env, "AGRARIA"), &addresses.storage);
+        client.set_producer_quota(&addresses.producer, &500_000);
+        client.set_producer_quota(&addresses.third_party, &500_000);
+
+        // Metadata naming another producer
+        let metadata = create_spot_metadata(&env, &addresses.third_party);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        assert!(
+            client
+                .try_producer_mint_spot(
+                    &addresses.producer,
+                    &series_id,
+                    &metadata,
+                    &create_standard_distribution(&addresses),
+                    &1_000,
+                )
+                .is_err()
+        );
+
+        // Re-minting an existing series owned by another producer
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        assert!(
+            client
+                .try_producer_mint_spot(
+                    &addresses.third_party,
+                    &series_id,
+                    &metadata,
+                    &create_standard_distribution(&addresses),
+                    &1_000,
+                )
+                .is_err()
+        );
+        assert_eq!(client.total_supply(), 1_000_000);
+    }
+
+    // Test 62: Amendments only reach non-binding fields
+    #[cfg(all(feature = "insights", feature = "series-admin"))]
+    #[test]
+    fn test_series_amendment_keeps_binding_terms() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let original = client.get_series(&series_id).unwrap();
+
+        env.ledger().set_timestamp(1741564800);
+        client.amend_series(
+            &series_id,
+            &MetadataUpdate {
+                location: Some(String::from_str(&env, "GO")),
+                metadata_uri: None,
+            },
+        );
+
+        let info = client.series_info(&series_id).unwrap();
+        assert_eq!(info.metadata.location, String::from_str(&env, "GO"));
+        assert_eq!(info.metadata.contract_hash, original.contract_hash);
+        assert_eq!(info.metadata.delivery_date, original.delivery_date);
+        assert_eq!(info.metadata.quantity_kg, original.quantity_kg);
+        assert_eq!(info.metadata.currency, original.currency);
+        assert_eq!(info.metadata.producer, original.producer);
+        assert_eq!(info.stamps.updated_at, 1741564800);
+    }
+
+    // Test 63: Binding terms cannot be smuggled in by crafted updates or internal calls
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_binding_terms_cannot_be_smuggled() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let original = client.get_series(&series_id).unwrap();
+
+        // An update carrying extra fields does not decode as a MetadataUpdate
+        let mut crafted: Map<Symbol, Val> = Map::new(&env);
+        crafted.set(
+            Symbol::new(&env, "location"),
+            Some(String::from_str(&env, "GO")).into_val(&env),
+        );
+        crafted.set(
+            Symbol::new(&env, "metadata_uri"),
+            None::<String>.into_val(&env),
+        );
+        crafted.set(
+            Symbol::new(&env, "contract_hash"),
+            String::from_str(&env, "0xdeadbeef").into_val(&env),
+        );
+        let result = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &client.address,
+            &Symbol::new(&env, "amend_series"),
+            vec![&env, series_id.into_val(&env), crafted.into_val(&env)],
+        );
+        assert!(result.is_err());
+
+        crafted.remove(Symbol::new(&env, "contract_hash"));
+        let result = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &client.address,
+            &Symbol::new(&env, "amend_series"),
+            vec![&env, series_id.into_val(&env), crafted.into_val(&env)],
+        );
+        assert!(result.is_ok());
+
+        // The internal helper has no way to reach binding fields either
+        env.as_contract(&client.address, || {
+            ContangoToken::apply_metadata_update(
+                &env,
+                &series_id,
+                &MetadataUpdate {
+                    location: Some(String::from_str(&env, "GO")),
+                    metadata_uri: None,
+                },
+            );
+        });
+
+        let stored = client.get_series(&series_id).unwrap();
+        assert_eq!(stored.contract_hash, original.contract_hash);
+        assert_eq!(stored.delivery_date, original.delivery_date);
+        assert_eq!(stored.quantity_kg, original.quantity_kg);
+
+        // Re-minting the series with different terms is rejected
+        let mut altered = create_spot_metadata(&env, &addresses.producer);
+        altered.contract_hash = String::from_str(&env, "0xdeadbeef");
+        assert!(
+            client
+                .try_mint_spot(
+                    &addresses.admin,
+                    &series_id,
+                    &altered,
+                    &create_standard_distribution(&addresses),
+                    &1
+                )
+                .is_err()
+        );
+        assert_eq!(
+            client.get_series(&series_id).unwrap().contract_hash,
+            original.contract_hash
+        );
+    }
+
+    // Test 64: Views degrade gracefully on an uninitialized contract
+    #[cfg(all(
+        feature = "allowlists",
+        feature = "insights",
+        feature = "producers",
+        feature = "recovery",
+        feature = "supply-controls"
+    ))]
+    #[test]
+    fn test_views_on_uninitialized_contract() {
+        let env = Env::default();
+        let client = ContangoTokenClient::new(&env, &env.register(ContangoToken, ()));
+        let owner = Address::generate(&env);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let facility = String::from_str(&env, "AGRARIA");
+
+        assert!(client.get_config().is_none());
+        assert!(client.token_metadata().is_none());
+        assert_eq!(client.name(), String::from_str(&env, ""));
+        assert_eq!(client.symbol(), String::from_str(&env, ""));
+        assert_eq!(client.decimals(), 0);
+        assert_eq!(client.total_supply(), 0);
+        assert_eq!(client.balance_of(&owner), 0);
+        assert_eq!(client.locked_balance_of(&owner), 0);
+        assert_eq!(client.statement_of(&owner, &0, &0).fees_paid, 0);
+        assert!(client.get_series(&series_id).is_none());
+        assert!(client.series_info(&series_id).is_none());
+        assert!(client.pending_delivery(&series_id).is_none());
+        assert_eq!(client.accrued_penalty(&series_id), 0);
+        assert!(client.is_series_recipient(&series_id, &owner));
+        assert!(client.get_facility(&facility).is_none());
+        assert!(client.get_producer_quota(&owner).is_none());
+        assert!(client.pending_assisted_burn(&series_id, &owner).is_none());
+        assert_eq!(client.monthly_issuance_cap(), 0);
+        assert!(client.pending_issuance_cap().is_none());
+        assert_eq!(client.issuance_remaining(), None);
+        assert_eq!(client.issuance_epoch(), (0, 2678400));
+    }
+
+    // Test 65: Transfer events always reconcile gross = net + fee
+    #[test]
+    fn test_transfer_event_amounts_reconcile() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_transfer_fee(&addresses.admin, &333); // 3.33%, forces rounding
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        for exempt in [false, true] {
+            client.set_fee_exempt(&addresses.third_party, &exempt);
+            let before = client.balance_of(&addresses.producer);
+            client.transfer(&addresses.producer, &addresses.third_party, &12_345);
+
+            let (_, data) = last_event(&env, "transfer_amounts");
+            let amounts: TransferAmounts = data.into_val(&env);
+            assert_eq!(amounts.gross, amounts.net + amounts.fee);
+            assert_eq!(
+                amounts.gross,
+                before - client.balance_of(&addresses.producer)
+            );
+            assert_eq!(amounts.fee_recipient.is_some(), !exempt);
+        }
+    }
+
+    // Test 66: Series status drives which series can be confirmed
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_series_status_gates_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        assert_eq!(client.series_status(&spot), Some(SeriesStatus::Active));
+        assert_eq!(
+            client.series_status(&future),
+            Some(SeriesStatus::DeliveryPending)
+        );
+        assert_eq!(
+            client.series_status(&String::from_str(&env, "CTGSoy-BRL-2025Q9")),
+            None
+        );
+
+        // Dry runs report the same blocking reasons
+        let report = client.simulate(&vec![
+            &env,
+            SimOp::ConfirmDelivery(spot.clone()),
+            SimOp::ConfirmDelivery(future.clone()),
+            SimOp::ConfirmDelivery(future.clone()),
+        ]);
+        assert_eq!(
+            report.results,
+            vec![
+                &env,
+                SimResult::NotFutureContract,
+                SimResult::Ok,
+                SimResult::AlreadyDelivered,
+            ]
+        );
+
+        client.confirm_delivery(&future, &addresses.storage);
+        assert_eq!(client.series_status(&future), Some(SeriesStatus::Delivered));
+        assert!(
+            client
+                .try_confirm_delivery(&spot, &addresses.storage)
+                .is_err()
+        );
+    }
+
+    // Test 67: Storage-side fees follow the facility's weighted recipients
+    #[cfg(feature = "facility-ops")]
+    #[test]
+    fn test_facility_fee_recipients_split() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let silo_a = Address::generate(&env);
+        let silo_b = Address::generate(&env);
+        client.set_facility_fee_recipients(
+            &String::from_str(&env, "AGRARIA"),
+            &vec![
+                &env,
+                FeeShare {
+                    recipient: silo_a.clone(),
+                    bps: 6000,
+                },
+                FeeShare {
+                    recipient: silo_b.clone(),
+                    bps: 4000,
+                },
+            ],
+        );
+
+        // Mint storage share: 0.5% of 1_000_001 = 5_000, split 3_000 / 2_000
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 1_000_001;
+        client.mint_spot(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+            &metadata,
+            &create_standard_distribution(&addresses),
+            &1_000_001,
+        );
+        assert_eq!(client.balance_of(&silo_a), 3_000);
+        assert_eq!(client.balance_of(&silo_b), 2_000);
+        assert_eq!(client.balance_of(&addresses.storage), 0);
+
+        // Burn storage share: 0.5% of 100_100 = 500, half = 250, split 150 / 100
+        client.burn_series(
+            &addresses.producer,
+            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+            &100_100,
+        );
+        assert_eq!(client.balance_of(&silo_a), 3_150);
+        assert_eq!(client.balance_of(&silo_b), 2_100);
+
+        // Odd amounts round down for the first recipient, the last takes the remainder
+        client.burn_series(
+            &addresses.producer,
+            &String::from_str(&env, "CTGSoy-BRL-2025Q1"),
+            &1_800,
+        );
+        // Fee 9, storage half 5: 60% = 3, remainder 2
+        assert_eq!(client.balance_of(&silo_a), 3_153);
+        assert_eq!(client.balance_of(&silo_b), 2_102);
+    }
+
+    // Test 68: Fee recipient weights must be complete and bounded
+    #[cfg(feature = "facility-ops")]
+    #[test]
+    fn test_facility_fee_recipients_must_sum() {
+        let (env, client, _addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        assert_contract_error(
+            client.try_set_facility_fee_recipients(
+                &String::from_str(&env, "AGRARIA"),
+                &vec![
+                    &env,
+                    FeeShare {
+                        recipient: Address::generate(&env),
+                        bps: 6000,
+                    },
+                ],
+            ),
+            ContractError::InvalidDistribution,
+        );
+    }
+
+    // Test 69: Fully offsetting legs move nothing and charge no fee
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_net_settle_full_offset() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.transfer(&addresses.producer, &addresses.third_party, &200_000);
+        client.set_transfer_fee(&addresses.admin, &100);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let admin_before = client.balance_of(&addresses.admin);
+
+        client.net_settle(
+            &addresses.producer,
+            &addresses.third_party,
+            &vec![
+                &env,
+                (series_id.clone(), 150_000_i128),
+                (series_id.clone(), -150_000_i128),
+            ],
+        );
+
+        assert_eq!(client.balance_of(&addresses.producer), 790_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 200_000);
+        assert_eq!(client.balance_of(&addresses.admin), admin_before);
+    }
+
+    // Test 70: Partially offsetting legs settle only the net per series
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_net_settle_partial_offset() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1_000_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 1_000_000);
+        let q1 = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let q2 = String::from_str(&env, "CTGSoy-BRL-2025Q2");
+        client.transfer_series(&addresses.producer, &addresses.third_party, &q1, &250_000);
+        client.transfer_series(&addresses.producer, &addresses.third_party, &q2, &250_000);
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+        let admin_before = client.balance_of(&addresses.admin);
+
+        client.net_settle(
+            &addresses.producer,
+            &addresses.third_party,
+            &vec![
+                &env,
+                (q1.clone(), 100_000_i128),
+                (q2.clone(), -10_000_i128),
+                (q1.clone(), -40_000_i128),
+            ],
+        );
+
+        let auths = env.auths();
+        assert_eq!(auths.len(), 2);
+
+        // Q1 nets 60_000 a->b, Q2 nets 10_000 b->a, 1% fee on each
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            1_480_000 - 60_000 + 9_900
+        );
+        assert_eq!(
+            client.balance_of(&addresses.third_party),
+            500_000 + 59_400 - 10_000
+        );
+        assert_eq!(
+            client.balance_of(&addresses.admin),
+            admin_before + 600 + 100
+        );
+    }
+
+    // Test 71: A netting that cannot be covered reverts as a whole
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_net_settle_is_atomic() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1_000_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 1_000_000);
+        let q1 = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let q2 = String::from_str(&env, "CTGSoy-BRL-2025Q2");
+
+        // The first leg succeeds on its own, the second cannot be covered by the counterparty
+        let result = client.try_net_settle(
+            &addresses.producer,
+            &addresses.third_party,
+            &vec![&env, (q1, 100_000_i128), (q2, -200_000_i128)],
+        );
+
+        assert!(result.is_err());
+        assert_eq!(client.balance_of(&addresses.producer), 1_980_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 0);
+    }
+
+    // Test 72: The supply cap holds across mint paths until a timelocked raise
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_supply_cap_and_timelocked_raise() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = ContangoTokenClient::new(&env, &env.register(ContangoToken, ()));
+        let addresses = TestAddresses {
+            admin: Address::generate(&env),
+            storage: Address::generate(&env),
+            producer: Address::generate(&env),
+            buyer: Address::generate(&env),
+            guarantee_agent: Address::generate(&env),
+            third_party: Address::generate(&env),
+        };
+        client.initialize(
+            &String::from_str(&env, "Contango Token"),
+            &String::from_str(&env, "CTG"),
+            &addresses.admin,
+            &addresses.storage,
+            &String::from_str(&env, "contango.com.br"),
+            &1_500_000,
+        );
+
+        assert_eq!(client.supply_cap(), 1_500_000);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        assert_eq!(client.supply_headroom(), Some(0));
+
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 1;
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q2");
+        let distribution = create_standard_distribution(&addresses);
+        assert!(
+            client
+                .try_mint_spot(&addresses.admin, &series_id, &metadata, &distribution, &1)
+                .is_err()
+        );
+
+        // Lowering is not a raise; the raise waits out its timelock
+        assert!(client.try_raise_supply_cap(&1_000_000).is_err());
+        client.raise_supply_cap(&2_000_000);
+        assert!(client.try_apply_supply_cap().is_err());
+        assert_eq!(client.supply_cap(), 1_500_000);
+
+        env.ledger().set_timestamp(7 * 24 * 60 * 60);
+        client.apply_supply_cap();
+        assert!(has_event(&env, "supply_cap_raised"));
+        assert_eq!(client.supply_headroom(), Some(500_000));
+
+        client.mint_spot(&addresses.admin, &series_id, &metadata, &distribution, &1);
+        assert_eq!(client.total_supply(), 1_500_001);
+    }
+
+    // Test 73: Uncapped contracts report no headroom limit
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_supply_uncapped_by_default() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        assert_eq!(client.supply_cap(), 0);
+        assert_eq!(client.supply_headroom(), None);
+        assert!(client.try_raise_supply_cap(&2_000_000).is_err());
+    }
+
+    // Test 74: A defaulted future redirects the guarantee fee to the buyer
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_default_redirects_agent_fee() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        assert_eq!(client.agent_escrow(&series_id).unwrap().amount, 2_500);
+
+        client.settle_default(&series_id);
+
+        assert!(has_event(&env, "default_settled"));
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::Defaulted)
+        );
+        assert_eq!(client.balance_of(&addresses.buyer), 2_500);
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 0);
+        assert_eq!(client.agent_pending_earnings(&addresses.guarantee_agent), 0);
+        assert!(client.agent_escrow(&series_id).is_none());
+        assert_eq!(client.total_supply(), 500_000);
+
+        // A defaulted series can no longer be delivered
+        assert!(
+            client
+                .try_confirm_delivery(&series_id, &addresses.storage)
+                .is_err()
+        );
+    }
+
+    // Test 75: Recent operations are kept in a bounded ring buffer
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_recent_ops_ring_buffer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(42);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let first = client.op_by_seq(&1).unwrap();
+        assert_eq!(first.op_kind, Symbol::new(&env, "mint_spot"));
+        assert_eq!(first.actor, addresses.admin);
+        assert_eq!(
+            first.series,
+            Some(String::from_str(&env, "CTGSoy-BRL-2025Q1"))
+        );
+        assert_eq!(first.amount, 1_000_000);
+        assert_eq!(first.ledger, 42);
+
+        for i in 0..205 {
+            client.transfer(&addresses.producer, &addresses.third_party, &(i + 1));
+        }
+
+        // 206 operations logged, the oldest 6 evicted
+        for seq in 1..=6 {
+            assert!(client.op_by_seq(&seq).is_none());
+        }
+        let oldest = client.op_by_seq(&7).unwrap();
+        assert_eq!(oldest.op_kind, Symbol::new(&env, "transfer"));
+        assert_eq!(oldest.amount, 6);
+        assert!(client.op_by_seq(&207).is_none());
+
+        let newest = client.recent_ops(&0);
+        assert_eq!(newest.len(), 20);
+        assert_eq!(newest.get(0).unwrap().seq, 206);
+        assert_eq!(newest.get(19).unwrap().seq, 187);
+
+        let last_page = client.recent_ops(&9);
+        assert_eq!(last_page.len(), 20);
+        assert_eq!(last_page.get(19).unwrap().seq, 7);
+        assert!(client.recent_ops(&10).is_empty());
+    }
+
+    // Test 76: Facility operators attest inventory and staleness follows ledger time
+    #[cfg(all(feature = "insights", feature = "producers"))]
+    #[test]
+    fn test_inventory_attestations() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let facility = String::from_str(&env, "AGRARIA");
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        client.set_facility_operator(&facility, &addresses.storage);
+
+        assert!(client.is_backing_stale(&series_id, &(30 * 24 * 60 * 60)));
+        client.attest_inventory(
+            &facility,
+            &series_id,
+            &999_000,
+            &String::from_str(&env, "0xfeedface"),
+        );
+        assert!(has_event(&env, "backing_shortfall"));
+        assert_eq!(env.auths()[0].0, addresses.storage);
+
+        let attestation = client.latest_attestation(&series_id).unwrap();
+        assert_eq!(attestation.kg_on_hand, 999_000);
+        assert_eq!(attestation.attested_at, 1741564800);
+        assert!(!client.is_backing_stale(&series_id, &(30 * 24 * 60 * 60)));
+
+        env.ledger().set_timestamp(1741564800 + 31 * 24 * 60 * 60);
+        assert!(client.is_backing_stale(&series_id, &(30 * 24 * 60 * 60)));
+
+        // Attestations must come from the facility holding the series
+        assert!(
+            client
+                .try_attest_inventory(
+                    &String::from_str(&env, "SLC"),
+                    &series_id,
+                    &1_000_000,
+                    &String::from_str(&env, "0xfeedface"),
+                )
+                .is_err()
+        );
+    }
+
+    // Test 77: Stale backing pauses series movements until a fresh attestation
+    #[cfg(all(feature = "insights", feature = "producers"))]
+    #[test]
+    fn test_stale_backing_auto_pause() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let facility = String::from_str(&env, "AGRARIA");
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        client.set_facility_operator(&facility, &addresses.storage);
+        client.attest_inventory(
+            &facility,
+            &series_id,
+            &1_000_000,
+            &String::from_str(&env, "0xfeedface"),
+        );
+        client.set_attestation_max_age(&(30 * 24 * 60 * 60));
+
+        client.burn_series(&addresses.producer, &series_id, &1_000);
+
+        env.ledger().set_timestamp(1741564800 + 31 * 24 * 60 * 60);
+        assert!(
+            client
+                .try_burn_series(&addresses.producer, &series_id, &1_000)
+                .is_err()
+        );
+
+        client.attest_inventory(
+            &facility,
+            &series_id,
+            &1_000_000,
+            &String::from_str(&env, "0xc0ffee"),
+        );
+        client.burn_series(&addresses.producer, &series_id, &1_000);
+        assert_eq!(client.balance_of(&addresses.producer), 988_000);
+    }
+
+    // Test 78: Producer records count on-time, late and defaulted futures
+    #[cfg(all(feature = "defaults", feature = "producers"))]
+    #[test]
+    fn test_producer_record() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let metadata = create_future_metadata(&env, &addresses); // Due 1751328000
+        for series in [
+            "CTGSoy-USD-2025Q4",
+            "CTGSoy-USD-2026Q1",
+            "CTGSoy-USD-2026Q2",
+        ] {
+            client.mint_future(
+                &addresses.admin,
+                &String::from_str(&env, series),
+                &metadata,
+                &Address::generate(&env),
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            );
+        }
+
+        env.ledger().set_timestamp(1751328000);
+        client.confirm_delivery(
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &addresses.storage,
+        );
+        env.ledger().set_timestamp(1751328001);
+        client.confirm_delivery(
+            &String::from_str(&env, "CTGSoy-USD-2026Q1"),
+            &addresses.storage,
+        );
+        client.settle_default(&String::from_str(&env, "CTGSoy-USD-2026Q2"));
+
+        let record = client.producer_record(&addresses.producer);
+        assert_eq!(record.on_time, 1);
+        assert_eq!(record.late, 1);
+        assert_eq!(record.defaulted, 1);
+        assert_eq!(record.total_kg_delivered, 1_000_000);
+        assert_eq!(client.producer_record(&addresses.buyer).on_time, 0);
+    }
+
+    // Test 79: Fee exemption is contract policy; the legacy apply_fee flag is ignored
+    #[test]
+    fn test_fee_exemption_policy() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        // Legacy callers passing false still pay the fee and get a deprecation event
+        client.transfer_with_fee(
+            &addresses.producer,
+            &addresses.buyer,
+            &100_000,
+            &Some(false),
+        );
+        let (_, topics, data) = env.events().all().first().unwrap();
+        let topic: Symbol = topics.get(0).unwrap().into_val(&env);
+        assert_eq!(topic, Symbol::new(&env, "apply_fee_deprecated"));
+        let flag: bool = data.into_val(&env);
+        assert!(!flag);
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+
+        // An exempt sender or recipient skips the fee
+        client.set_fee_exempt(&addresses.buyer, &true);
+        assert!(client.is_fee_exempt(&addresses.buyer));
+        client.transfer(&addresses.buyer, &addresses.third_party, &10_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 10_000);
+        client.transfer(&addresses.producer, &addresses.buyer, &10_000);
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+
+        // Revoking the exemption restores the fee
+        client.set_fee_exempt(&addresses.buyer, &false);
+        assert!(!client.is_fee_exempt(&addresses.buyer));
+        client.transfer(&addresses.producer, &addresses.buyer, &10_000);
+        assert_eq!(client.balance_of(&addresses.buyer), 108_900);
+    }
+
+    #[cfg(all(feature = "batch", feature = "insights"))]
+    fn mint_batch_futures(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let metadata = create_future_metadata(env, addresses);
+        for i in 0..5u32 {
+            let series_id = String::from_str(env, ["F1", "F2", "F3", "F4", "F5"][i as usize]);
+            client.mint_future(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &Address::generate(env),
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            );
+        }
+    }
+
+    // Test 80: One validator confirms five series in a single batch, one of them partially
+    #[cfg(all(feature = "batch", feature = "insights", feature = "producers"))]
+    #[test]
+    fn test_confirm_delivery_batch() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_batch_futures(&env, &client, &addresses);
+        let ids = ["F1", "F2", "F3", "F4", "F5"];
+        let mut items = vec![&env];
+        for id in ids {
+            let amount = if id == "F5" { 40_000 } else { 99_000 };
+            items.push_back((String::from_str(&env, id), amount));
+        }
+
+        client.confirm_delivery_batch(&addresses.storage, &items);
+        assert_eq!(env.auths().len(), 1);
+        assert_eq!(env.auths()[0].0, addresses.storage);
+
+        for id in ids {
+            let series_id = String::from_str(&env, id);
+            let buyer = client
+                .series_info(&series_id)
+                .unwrap()
+                .metadata
+                .buyer
+                .unwrap();
+            if id == "F5" {
+                assert_eq!(
+                    client.series_status(&series_id),
+                    Some(SeriesStatus::DeliveryPending)
+                );
+                assert_eq!(client.balance_of(&buyer), 40_000);
+                assert_eq!(client.statement_of(&buyer, &0, &0).locked_balance, 59_000);
+                assert!(client.agent_escrow(&series_id).is_some());
+            } else {
+                assert_eq!(
+                    client.series_status(&series_id),
+                    Some(SeriesStatus::Delivered)
+                );
+                assert_eq!(client.balance_of(&buyer), 99_000);
+                assert!(client.agent_escrow(&series_id).is_none());
+            }
+        }
+        assert_eq!(client.producer_record(&addresses.producer).on_time, 4);
+
+        // The remainder of the partial series completes it
+        client.confirm_delivery(&String::from_str(&env, "F5"), &addresses.storage);
+        assert_eq!(
+            client.series_status(&String::from_str(&env, "F5")),
+            Some(SeriesStatus::Delivered)
+        );
+    }
+
+    // Test 81: A paused series anywhere in the batch reverts every confirmation
+    #[cfg(all(feature = "batch", feature = "insights", feature = "producers"))]
+    #[test]
+    fn test_confirm_delivery_batch_atomic() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_batch_futures(&env, &client, &addresses);
+        let facility = String::from_str(&env, "SLC");
+        client.set_facility_operator(&facility, &addresses.storage);
+        let mut items = vec![&env];
+        for id in ["F1", "F2", "F3", "F4", "F5"] {
+            let series_id = String::from_str(&env, id);
+            if id != "F3" {
+                client.attest_inventory(
+                    &facility,
+                    &series_id,
+                    &500_000,
+                    &String::from_str(&env, "0xfeedface"),
+                );
+            }
+            items.push_back((series_id, 99_000));
+        }
+        client.set_attestation_max_age(&(30 * 24 * 60 * 60));
+
+        assert!(
+            client
+                .try_confirm_delivery_batch(&addresses.storage, &items)
+                .is_err()
+        );
+        assert!(
+            env.logs()
+                .all()
+                .iter()
+                .any(|line| line.contains("confirm_delivery_batch rejected series"))
+        );
+        for id in ["F1", "F2", "F3", "F4", "F5"] {
+            assert_eq!(
+                client.series_status(&String::from_str(&env, id)),
+                Some(SeriesStatus::DeliveryPending)
+            );
+        }
+        assert_eq!(client.producer_record(&addresses.producer).on_time, 0);
+    }
+
+    // Test 82: Metadata URIs must be https:// or ipfs:// and are announced at creation
+    #[test]
+    fn test_metadata_uri_validation() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let distribution = create_standard_distribution(&addresses);
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        let long_uri = "https://example.com/".repeat(13); // 260 bytes
+        for bad in [
+            "http://example.com/receipt.json",
+            "ftp://x",
+            long_uri.as_str(),
+        ] {
+            metadata.metadata_uri = Some(String::from_str(&env, bad));
+            assert!(
+                client
+                    .try_mint_spot(
+                        &addresses.admin,
+                        &series_id,
+                        &metadata,
+                        &distribution,
+                        &1_000
+                    )
+                    .is_err()
+            );
+        }
+
+        let uri = String::from_str(
+            &env,
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        );
+        metadata.metadata_uri = Some(uri.clone());
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &1_000,
+        );
+
+        let (_, _, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap()).ok()
+                    == Some(Symbol::new(&env, "series_created"))
+            })
+            .unwrap();
+        let (announced, lot): (Option<String>, SeriesLot) = data.into_val(&env);
+        assert_eq!(announced, Some(uri.clone()));
+        assert_eq!(lot, SeriesLot::Bulk);
+        assert_eq!(
+            client.get_series(&series_id).unwrap().metadata_uri,
+            Some(uri)
+        );
+
+        // Topping up an existing series does not announce it again
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &1_000,
+        );
+        assert!(!has_event(&env, "series_created"));
+    }
+
+    // Test 83: Amending the metadata URI reports the old and new values
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_metadata_uri_amendment() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let first = String::from_str(&env, "https://docs.contango.example/receipts/1.json");
+        let second = String::from_str(
+            &env,
+            "ipfs://bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+        );
+
+        client.amend_series(
+            &series_id,
+            &MetadataUpdate {
+                location: None,
+                metadata_uri: Some(first.clone()),
+            },
+        );
+        client.amend_series(
+            &series_id,
+            &MetadataUpdate {
+                location: None,
+                metadata_uri: Some(second.clone()),
+            },
+        );
+
+        let (_, _, data) = env.events().all().first().unwrap();
+        let (old, new): (Option<String>, Option<String>) = data.into_val(&env);
+        assert_eq!(old, Some(first));
+        assert_eq!(new, Some(second.clone()));
+        assert_eq!(
+            client.get_series(&series_id).unwrap().metadata_uri,
+            Some(second)
+        );
+
+        assert!(
+            client
+                .try_amend_series(
+                    &series_id,
+                    &MetadataUpdate {
+                        location: None,
+                        metadata_uri: Some(String::from_str(&env, "javascript:alert(1)")),
+                    },
+                )
+                .is_err()
+        );
+    }
+
+    // Test 84: Swaps cannot push a destination past its backed kg
+    #[cfg(all(feature = "series-admin", feature = "swaps"))]
+    #[test]
+    fn test_swap_into_full_series_rejected() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+
+        // Exactly the remaining 500_000 kg fits
+        set_fx_price(&env, &client, 10000);
+        client.swap(&addresses.producer, &brl, &usd, &500_000, &0);
+        assert_eq!(client.series_supply(&usd), 1_000_000);
+        assert_eq!(client.series_supply(&brl), 500_000);
+
+        // The destination now has zero capacity left
+        let before = client.balance_of(&addresses.producer);
+        assert!(
+            client
+                .try_swap(&addresses.producer, &brl, &usd, &1, &0)
+                .is_err()
+        );
+        assert_eq!(client.balance_of(&addresses.producer), before);
+        assert_eq!(client.series_supply(&brl), 500_000);
+
+        // Burning from the destination frees capacity only once its kg are re-certified
+        client.burn_series(&addresses.producer, &usd, &100_000);
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &brl, &usd, &10_000, &0),
+            ContractError::SeriesAtCapacity,
+        );
+        client.adjust_series_quantity(&usd, &1_000_000);
+        client.swap(&addresses.producer, &brl, &usd, &10_000, &0);
+        assert_eq!(client.series_supply(&brl), 490_000);
+    }
+
+    // Test 85: Swaps can be frozen while transfers and deliveries continue
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_freeze() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        assert!(client.swaps_enabled());
+
+        client.set_swaps_enabled(&addresses.admin, &false);
+        assert!(has_event(&env, "swaps_enabled"));
+        assert_eq!(env.auths()[0].0, addresses.admin);
+        assert!(!client.swaps_enabled());
+        assert!(
+            client
+                .try_swap(&addresses.producer, &brl, &usd, &100_000, &0)
+                .is_err()
+        );
+
+        client.transfer(&addresses.producer, &addresses.buyer, &100_000);
+        assert_eq!(client.balance_of(&addresses.buyer), 100_000);
+
+        client.set_swaps_enabled(&addresses.admin, &true);
+        client.swap(&addresses.producer, &brl, &usd, &100_000, &0);
+        assert_eq!(client.series_supply(&usd), 555_000);
+    }
+
+    // Test 86: Mints feed the insurance buffer, which pays claims under co-authorization
+    #[cfg(all(feature = "insights", feature = "insurance"))]
+    #[test]
+    fn test_insurance_buffer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_insurance_reserve(&10); // 0.1%
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 2_000_000;
+        let distribution = create_standard_distribution(&addresses);
+        client.mint_spot(
+            &addresses.admin,
+            &spot,
+            &metadata,
+            &distribution,
+            &1_000_000,
+        );
+        assert!(has_event(&env, "insurance_reserved"));
+        client.mint_spot(
+            &addresses.admin,
+            &spot,
+            &metadata,
+            &distribution,
+            &1_000_000,
+        );
+        client.mint_future(
+            &addresses.admin,
+            &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+
+        assert_eq!(client.insurance_buffer(), 2_500);
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            2 * (990_000 - 1_000)
+        );
+        assert_eq!(
+            client.statement_of(&addresses.buyer, &0, &0).locked_balance,
+            495_000 - 500
+        );
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        assert!(
+            client
+                .try_pay_insurance(&series_id, &addresses.buyer, &2_501)
+                .is_err()
+        );
+
+        client.pay_insurance(&series_id, &addresses.buyer, &2_000);
+        assert_eq!(env.auths().len(), 2);
+        assert_eq!(env.auths()[0].0, addresses.admin);
+        assert_eq!(env.auths()[1].0, addresses.guarantee_agent);
+        assert_eq!(client.balance_of(&addresses.buyer), 2_000);
+        assert_eq!(client.insurance_buffer(), 500);
+    }
+
+    // Test 87: Series-aware transfers carry the series id as a filterable topic
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_transfer_topic_layouts() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1_000_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 1_000_000);
+        let q1 = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let q2 = String::from_str(&env, "CTGSoy-BRL-2025Q2");
+        let transfer = Symbol::new(&env, "transfer_amounts");
+
+        // Aggregate-balance transfers keep the legacy shape
+        client.transfer(&addresses.producer, &addresses.buyer, &1_000);
+        let (topics, _) = last_event(&env, "transfer_amounts");
+        assert_eq!(topics.len(), TRANSFER_TOPICS);
+        let expected = (
+            transfer.clone(),
+            addresses.producer.clone(),
+            addresses.buyer.clone(),
+        )
+            .into_val(&env);
+        assert_eq!(topics, expected);
+
+        client.net_settle(
+            &addresses.producer,
+            &addresses.buyer,
+            &vec![&env, (q1.clone(), 10_000), (q2.clone(), 20_000)],
+        );
+        let mut q1_transfers = 0;
+        for (_, topics, data) in env.events().all().iter() {
+            if topics.len() != SERIES_TRANSFER_TOPICS {
+                continue;
+            }
+            let expected = (
+                transfer.clone(),
+                addresses.producer.clone(),
+                addresses.buyer.clone(),
+                q1.clone(),
+            )
+                .into_val(&env);
+            if String::try_from_val(&env, &topics.get(3).unwrap()).ok() == Some(q1.clone()) {
+                assert_eq!(topics, expected);
+                let amounts: TransferAmounts = data.into_val(&env);
+                assert_eq!(amounts.gross, 10_000);
+                q1_transfers += 1;
+            }
+        }
+        assert_eq!(q1_transfers, 1);
+
+        // The SEP-41 events stay at the standard three topics
+        let (topics, data) = last_event(&env, "transfer");
+        assert_eq!(topics.len(), TRANSFER_TOPICS);
+        assert_eq!(i128::try_from_val(&env, &data).unwrap(), 20_000);
+    }
+
+    // Test 88: Delivery events name the validator and the facility holding the series
+    #[cfg(all(
+        feature = "batch",
+        feature = "insights",
+        any(feature = "facility-ops", feature = "producers", feature = "recovery")
+    ))]
+    #[test]
+    fn test_delivery_event_names_validator() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_batch_futures(&env, &client, &addresses);
+        let facility = String::from_str(&env, "SLC");
+        client.set_facility_operator(&facility, &addresses.storage);
+        let operator = client.get_facility(&facility).unwrap().operator.unwrap();
+
+        client.confirm_delivery_batch(
+            &addresses.storage,
+            &vec![&env, (String::from_str(&env, "F1"), 40_000)],
+        );
+        let (_, _, data) = env.events().all().last().unwrap();
+        let partial: DeliveryEvent = data.into_val(&env);
+        assert_eq!(partial.amount, 40_000);
+
+        client.confirm_delivery(&String::from_str(&env, "F1"), &addresses.storage);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        let topic: Symbol = topics.get(0).unwrap().into_val(&env);
+        assert_eq!(topic, Symbol::new(&env, "delivery_confirmed"));
+        let confirmed: DeliveryEvent = data.into_val(&env);
+        assert_eq!(
+            confirmed,
+            DeliveryEvent {
+                amount: 59_000,
+                validator: operator.clone(),
+                facility: facility.clone(),
+                fee: 0,
+            }
+        );
+        assert_eq!(partial.validator, operator);
+        assert_eq!(partial.facility, facility);
+    }
+
+    // Test 89: Amended metadata reads the same through every public path
+    #[cfg(all(feature = "insights", feature = "series-admin", feature = "swaps"))]
+    #[test]
+    fn test_series_metadata_single_source() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let go = String::from_str(&env, "GO");
+
+        client.amend_series(
+            &usd,
+            &MetadataUpdate {
+                location: Some(go.clone()),
+                metadata_uri: None,
+            },
+        );
+        client.transfer_custody(&usd, &String::from_str(&env, "AGRARIA"));
+
+        let stored = client.get_series(&usd).unwrap();
+        let info = client.series_info(&usd).unwrap();
+        assert_eq!(stored.location, go);
+        assert_eq!(info.metadata.location, go);
+        assert_eq!(stored.storage_facility, String::from_str(&env, "AGRARIA"));
+        assert_eq!(info.metadata.storage_facility, stored.storage_facility);
+        assert_eq!(client.series_status(&usd), Some(SeriesStatus::Active));
+
+        // Swap validation reads the same record
+        client.swap(&addresses.producer, &brl, &usd, &100_000, &0);
+        assert_eq!(client.get_series(&usd).unwrap().location, go);
+
+        // Re-minting an existing series does not count it twice
+        client.mint_additional(
+            &addresses.admin,
+            &usd,
+            &create_standard_distribution(&addresses),
+            &1_000,
+        );
+        assert_eq!(client.token_metadata().unwrap().series_count, 2);
+    }
+
+    // Test 90: Allowances are spent by transfer_from, expire, and are overwritten on re-approval
+    #[test]
+    fn test_allowance_lifecycle() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(100);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let marketplace = Address::generate(&env);
+        client.approve(&addresses.producer, &marketplace, &300_000, &200);
+        assert_eq!(client.allowance(&addresses.producer, &marketplace), 300_000);
+
+        // Partial spends decrement the allowance
+        client.transfer_from(
+            &marketplace,
+            &addresses.producer,
+            &addresses.buyer,
+            &100_000,
+        );
+        assert_eq!(env.auths()[0].0, marketplace);
+        assert_eq!(client.allowance(&addresses.producer, &marketplace), 200_000);
+        assert_eq!(client.balance_of(&addresses.buyer), 100_000);
+
+        // Spending more than remains fails without moving tokens
+        assert!(
+            client
+                .try_transfer_from(
+                    &marketplace,
+                    &addresses.producer,
+                    &addresses.buyer,
+                    &200_001
+                )
+                .is_err()
+        );
+        assert_eq!(client.balance_of(&addresses.buyer), 100_000);
+
+        // Re-approval overwrites rather than adds
+        client.approve(&addresses.producer, &marketplace, &50_000, &200);
+        assert_eq!(client.allowance(&addresses.producer, &marketplace), 50_000);
+
+        // Past the expiration ledger the allowance reads as zero
+        env.ledger().set_sequence_number(201);
+        assert_eq!(client.allowance(&addresses.producer, &marketplace), 0);
+        assert!(
+            client
+                .try_transfer_from(&marketplace, &addresses.producer, &addresses.buyer, &1)
+                .is_err()
+        );
+    }
+
+    // Test 91: Approvals cannot expire in the past
+    #[test]
+    fn test_approve_past_expiration() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(100);
+
+        assert_contract_error(
+            client.try_approve(&addresses.producer, &addresses.buyer, &1_000, &99),
+            ContractError::InvalidExpiration,
+        );
+    }
+
+    // Test 92: Delivery credits the designated token recipient instead of the buyer
+    #[cfg(all(feature = "insights", feature = "series-admin"))]
+    #[test]
+    fn test_future_token_recipient() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let opco = Address::generate(&env);
+        let new_opco = Address::generate(&env);
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &Some(opco.clone()),
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        assert_eq!(client.token_recipient(&series_id), Some(opco));
+        assert_eq!(
+            client.statement_of(&addresses.buyer, &0, &0).locked_balance,
+            495_000
+        );
+
+        client.set_token_recipient(&series_id, &new_opco);
+        assert_eq!(env.auths()[0].0, addresses.buyer);
+        assert_eq!(client.token_recipient(&series_id), Some(new_opco.clone()));
+
+        client.confirm_delivery(&series_id, &addresses.storage);
+        assert_eq!(client.balance_of(&new_opco), 495_000);
+        assert_eq!(client.balance_of(&addresses.buyer), 0);
+
+        // The recipient is fixed once delivered
+        assert!(
+            client
+                .try_set_token_recipient(&series_id, &addresses.buyer)
+                .is_err()
+        );
+    }
+
+    // Test 93: Defaults still compensate the buyer, not the token recipient
+    #[cfg(feature = "defaults")]
+    #[test]
+    fn test_token_recipient_default_refunds_buyer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let opco = Address::generate(&env);
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &Some(opco.clone()),
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+
+        client.settle_default(&series_id);
+        assert_eq!(client.balance_of(&addresses.buyer), 2_500);
+        assert_eq!(client.balance_of(&opco), 0);
+    }
+
+    // Test 94: The standard token client drives the contract through SEP-41
+    #[test]
+    fn test_sep41_token_interface() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(100);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let token = TokenClient::new(&env, &client.address);
+        assert_eq!(token.name(), client.name());
+        assert_eq!(token.symbol(), client.symbol());
+        assert_eq!(token.decimals(), 0);
+        assert_eq!(token.balance(&addresses.producer), 990_000);
+
+        token.transfer(&addresses.producer, &addresses.buyer, &100_000);
+        let (_, topics, _) = env.events().all().last().unwrap();
+        let expected = (
+            Symbol::new(&env, "transfer"),
+            addresses.producer.clone(),
+            addresses.buyer.clone(),
+        )
+            .into_val(&env);
+        assert_eq!(topics, expected);
+        assert_eq!(token.balance(&addresses.buyer), 100_000);
+
+        let spender = Address::generate(&env);
+        token.approve(&addresses.buyer, &spender, &60_000, &200);
+        assert_eq!(token.allowance(&addresses.buyer, &spender), 60_000);
+        token.transfer_from(&spender, &addresses.buyer, &addresses.third_party, &10_000);
+        assert_eq!(token.balance(&addresses.third_party), 10_000);
+
+        // Plain burns destroy supply without touching any series
+        let supply = client.total_supply();
+        token.burn(&addresses.buyer, &5_000);
+        token.burn_from(&spender, &addresses.buyer, &5_000);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        let expected = (Symbol::new(&env, "burn"), addresses.buyer.clone()).into_val(&env);
+        assert_eq!(topics, expected);
+        assert_eq!(i128::try_from_val(&env, &data).unwrap(), 5_000);
+        assert_eq!(token.balance(&addresses.buyer), 80_000);
+        assert_eq!(token.allowance(&addresses.buyer, &spender), 45_000);
+        assert_eq!(client.total_supply(), supply - 10_000);
+    }
+
+    fn store_malformed_future(env: &Env, client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let mut metadata = create_future_metadata(env, addresses);
+        metadata.buyer = None;
+        metadata.guarantee_agent = None;
+        env.as_contract(&client.address, || {
+            env.storage().instance().set(
+                &DataKey::SeriesMetadata(metadata.id.clone()),
+                &SeriesRecord::V2(metadata.clone()),
+            );
+        });
+    }
+
+    // Test 95: A future stored without a buyer fails with a clear error
+    #[test]
+    fn test_malformed_future_missing_buyer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        store_malformed_future(&env, &client, &addresses);
+        assert_contract_error(
+            client.try_confirm_delivery(
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &addresses.storage,
+            ),
+            ContractError::MissingBuyer,
+        );
+    }
+
+    // Test 96: Insurance claims on a series without a guarantee agent fail with a clear error
+    #[cfg(feature = "insurance")]
+    #[test]
+    fn test_malformed_future_missing_guarantee_agent() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        store_malformed_future(&env, &client, &addresses);
+        assert_contract_error(
+            client.try_pay_insurance(
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &addresses.buyer,
+                &1,
+            ),
+            ContractError::MissingGuaranteeAgent,
+        );
+    }
+
+    // Test 97: Balances are tracked per series; plain transfers draw the oldest holding first
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_series_balances() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1_000_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 1_000_000);
+        let q1 = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let q2 = String::from_str(&env, "CTGSoy-BRL-2025Q2");
+        let q1_held = client.balance_of_series(&addresses.producer, &q1);
+        let q2_held = client.balance_of_series(&addresses.producer, &q2);
+        assert_eq!(client.balance_of(&addresses.producer), q1_held + q2_held);
+
+        client.transfer_series(&addresses.producer, &addresses.third_party, &q2, &100_000);
+        assert_eq!(
+            client.balance_of_series(&addresses.third_party, &q2),
+            100_000
+        );
+        assert_eq!(client.balance_of_series(&addresses.third_party, &q1), 0);
+
+        // Spending more than the Q1 holding spills over into Q2
+        client.transfer(
+            &addresses.producer,
+            &addresses.third_party,
+            &(q1_held + 50_000),
+        );
+        assert_eq!(client.balance_of_series(&addresses.producer, &q1), 0);
+        assert_eq!(
+            client.balance_of_series(&addresses.producer, &q2),
+            q2_held - 150_000
+        );
+        assert_eq!(
+            client.held_series(&addresses.producer),
+            vec![&env, q2.clone()]
+        );
+        assert_eq!(
+            client.held_series(&addresses.third_party),
+            vec![&env, q2.clone(), q1.clone()]
+        );
+        assert_eq!(client.balance_of(&addresses.third_party), q1_held + 150_000);
+    }
+
+    // Test 98: Redeeming a series you do not hold fails even with enough pooled balance
+    #[test]
+    fn test_burn_series_not_held() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q1", 1_000_000);
+        mint_facility_series(&env, &client, &addresses, "CTGSoy-BRL-2025Q2", 1_000_000);
+        let q1 = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        client.transfer_series(&addresses.producer, &addresses.third_party, &q1, &500_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 500_000);
+
+        assert_contract_error(
+            client.try_burn_series(
+                &addresses.third_party,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q2"),
+                &100_000,
+            ),
+            ContractError::InsufficientBalance,
+        );
+    }
+
+    // Test 99: Burns are counted by kind in the supply breakdown
+    #[cfg(all(feature = "insights", feature = "recovery"))]
+    #[test]
+    fn test_burn_breakdown() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+
+        propose_assisted_burn(&env, &client, &addresses);
+        assert_eq!(client.burn_breakdown().len(), 0);
+
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        client.burn(&addresses.producer, &50_000);
+        let events = env.events().all();
+        let event = events.get(events.len() - 2).unwrap(); // Followed by the SEP-41 burn event
+        assert_eq!(
+            event.1,
+            (Symbol::new(&env, "burn_recorded"), BurnKind::Buyback).into_val(&env)
+        );
+        assert_eq!(i128::try_from_val(&env, &event.2).unwrap(), 50_000);
+
+        env.ledger().set_timestamp(1741564800 + 7 * 24 * 60 * 60);
+        client.execute_assisted_burn(&series_id, &addresses.producer);
+
+        // Redemptions count what left supply, net of the 0.5% burn fee
+        let breakdown = client.burn_breakdown();
+        assert_eq!(breakdown.get(BurnKind::Redemption), Some(99_500));
+        assert_eq!(breakdown.get(BurnKind::Buyback), Some(50_000));
+        assert_eq!(breakdown.get(BurnKind::Correction), Some(300_000));
+        assert_eq!(breakdown.get(BurnKind::DefaultSettlement), None);
+        assert_eq!(breakdown.len(), 3);
+    }
+
+    // Test 100: Swaps burn the source series and mint the destination
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_moves_series_supply() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        assert_eq!(client.series_supply(&brl), 1_000_000);
+        assert_eq!(client.series_supply(&usd), 500_000);
+        assert_eq!(client.total_supply(), 1_500_000);
+        let brl_held = client.balance_of_series(&addresses.producer, &brl);
+        let usd_held = client.balance_of_series(&addresses.producer, &usd);
+
+        client.swap(&addresses.producer, &brl, &usd, &100_000, &0);
+        let event = env.events().all().last().unwrap();
+        assert_eq!(
+            event.1,
+            (Symbol::new(&env, "swap"), brl.clone(), usd.clone()).into_val(&env)
+        );
+        assert_eq!(
+            SwapEvent::try_from_val(&env, &event.2).unwrap(),
+            SwapEvent {
+                holder: addresses.producer.clone(),
+                amount_in: 100_000,
+                amount_out: 55_000,
+                oracle_price: 5500,
+            }
+        );
+
+        assert_eq!(client.series_supply(&brl), 900_000);
+        assert_eq!(client.series_supply(&usd), 555_000);
+        assert_eq!(client.total_supply(), 1_455_000);
+        assert_eq!(
+            client.balance_of_series(&addresses.producer, &brl),
+            brl_held - 100_000
+        );
+        assert_eq!(
+            client.balance_of_series(&addresses.producer, &usd),
+            usd_held + 55_000
+        );
+
+        // The pooled balance covers it, the source series does not
+        assert!(client.balance_of(&addresses.producer) > brl_held);
+        assert!(
+            client
+                .try_swap(&addresses.producer, &brl, &usd, &brl_held, &0)
+                .is_err()
+        );
+    }
+
+    #[cfg(all(
+        feature = "allowlists",
+        feature = "batch",
+        feature = "defaults",
+        feature = "facility-ops",
+        feature = "insights",
+        feature = "insurance",
+        feature = "producers",
+        feature = "recovery",
+        feature = "rewards",
+        feature = "series-admin",
+        feature = "supply-controls",
+        feature = "swaps"
+    ))]
+    fn assert_not_initialized<T, E: core::fmt::Debug>(result: Result<T, Result<Error, E>>) {
+        assert_contract_error(result, ContractError::NotInitialized);
+    }
+
+    // Test 101: Every mutating entry point fails with NotInitialized before initialize
+    #[cfg(all(
+        feature = "allowlists",
+        feature = "batch",
+        feature = "defaults",
+        feature = "facility-ops",
+        feature = "insights",
+        feature = "insurance",
+        feature = "producers",
+        feature = "recovery",
+        feature = "rewards",
+        feature = "series-admin",
+        feature = "supply-controls",
+        feature = "swaps"
+    ))]
+    #[test]
+    fn test_mutating_calls_require_initialization() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = ContangoTokenClient::new(&env, &env.register(ContangoToken, ()));
+        let addresses = TestAddresses {
+            admin: Address::generate(&env),
+            storage: Address::generate(&env),
+            producer: Address::generate(&env),
+            buyer: Address::generate(&env),
+            guarantee_agent: Address::generate(&env),
+            third_party: Address::generate(&env),
+        };
+        let (a, b) = (&addresses.producer, &addresses.buyer);
+        let id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let facility = String::from_str(&env, "AGRARIA");
+        let hash = String::from_str(&env, "0xabad1dea");
+        let spot = create_spot_metadata(&env, a);
+        let future = create_future_metadata(&env, &addresses);
+        let distribution = create_standard_distribution(&addresses);
+
+        assert_not_initialized(client.try_mint_spot(
+            &addresses.admin,
+            &id,
+            &spot,
+            &distribution,
+            &1,
+        ));
+        assert_not_initialized(client.try_producer_mint_spot(a, &id, &spot, &distribution, &1));
+        assert_not_initialized(client.try_mint_future(
+            &addresses.admin,
+            &id,
+            &future,
+            b,
+            &None,
+            &None,
+            &1,
+        ));
+        assert_not_initialized(client.try_confirm_delivery(&id, a));
+        assert_not_initialized(client.try_confirm_delivery_batch(a, &vec![&env, (id.clone(), 1)]));
+        assert_not_initialized(client.try_set_token_recipient(&id, a));
+        assert_not_initialized(client.try_settle_default(&id));
+        assert_not_initialized(client.try_accept_delivery(&id));
+        assert_not_initialized(client.try_finalize_delivery(&id));
+        assert_not_initialized(client.try_burn(a, &1));
+        assert_not_initialized(client.try_burn_from(b, a, &1));
+        assert_not_initialized(client.try_burn_series(a, &id, &1));
+        assert_not_initialized(client.try_assisted_burn(&id, a, &1, &hash));
+        assert_not_initialized(client.try_veto_assisted_burn(&id, a));
+        assert_not_initialized(client.try_execute_assisted_burn(&id, a));
+        assert_not_initialized(client.try_transfer(a, b, &1));
+        assert_not_initialized(client.try_transfer_series(a, b, &id, &1));
+        assert_not_initialized(client.try_transfer_with_fee(a, b, &1, &None));
+        assert_not_initialized(client.try_approve(a, b, &1, &1_000));
+        assert_not_initialized(client.try_transfer_from(b, a, b, &1));
+        assert_not_initialized(client.try_net_settle(a, b, &vec![&env, (id.clone(), 1)]));
+        assert_not_initialized(client.try_set_fee_exempt(a, &true));
+        assert_not_initialized(client.try_set_transfer_fee(&addresses.admin, &100));
+        assert_not_initialized(client.try_swap(a, &id, &id, &1, &0));
+        assert_not_initialized(client.try_swap_at_price(a, &id, &id, &1, &10000, &0));
+        assert_not_initialized(client.try_set_price_oracle(
+            &facility,
+            &Symbol::new(&env, "BRL_USD"),
+            a,
+        ));
+        assert_not_initialized(client.try_set_oracle_max_age(&1));
+        assert_not_initialized(client.try_set_insurance_reserve(&10));
+        assert_not_initialized(client.try_pay_insurance(&id, a, &1));
+        assert_not_initialized(client.try_set_swaps_enabled(a, &false));
+        assert_not_initialized(client.try_set_facility_capacity(&facility, &1));
+        assert_not_initialized(client.try_set_facility_operator(&facility, a));
+        assert_not_initialized(client.try_set_producer_quota(a, &1));
+        assert_not_initialized(client.try_set_confirmation_window(&facility, &None));
+        assert_not_initialized(client.try_set_confirmation_override(&facility, &true));
+        assert_not_initialized(client.try_set_guarantee_policy(&1, &true));
+        assert_not_initialized(client.try_set_facility_fee_recipients(&facility, &vec![&env]));
+        assert_not_initialized(client.try_attest_inventory(&facility, &id, &1, &hash));
+        assert_not_initialized(client.try_set_attestation_max_age(&1));
+        assert_not_initialized(client.try_set_capacity_warning_threshold(&1));
+        assert_not_initialized(client.try_amend_series(
+            &id,
+            &MetadataUpdate {
+                location: None,
+                metadata_uri: None,
+            },
+        ));
+        assert_not_initialized(client.try_transfer_custody(&id, &facility));
+        assert_not_initialized(client.try_set_series_allowlist_manager(&id, &true));
+        assert_not_initialized(client.try_add_series_recipient(&id, a));
+        assert_not_initialized(client.try_remove_series_recipient(&id, a));
+        assert_not_initialized(client.try_set_rewards_hook(&None));
+        assert_not_initialized(client.try_set_home_domain(&facility));
+        assert_not_initialized(client.try_set_acceptance_timeout(&1));
+        assert_not_initialized(client.try_schedule_issuance_cap(&1));
+        assert_not_initialized(client.try_apply_issuance_cap());
+        assert_not_initialized(client.try_raise_supply_cap(&1));
+        assert_not_initialized(client.try_apply_supply_cap());
+    }
+
+    // Test 102: Swaps honour the caller's minimum output, inclusive
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_min_amount_out() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+
+        // One basis point below the expected price falls short of 55_000
+        set_fx_price(&env, &client, 5499);
+        assert!(
+            client
+                .try_swap(&addresses.producer, &brl, &usd, &100_000, &55_000)
+                .is_err()
+        );
+        assert_eq!(client.series_supply(&usd), 500_000);
+
+        set_fx_price(&env, &client, 5500);
+        client.swap(&addresses.producer, &brl, &usd, &100_000, &55_000);
+        let event = env.events().all().last().unwrap();
+        assert_eq!(
+            SwapEvent::try_from_val(&env, &event.2).unwrap().amount_out,
+            55_000
+        );
+        assert_eq!(client.series_supply(&usd), 555_000);
+    }
+
+    // Test 103: Swaps need a configured, fresh oracle price unless the admin overrides it
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_oracle_price() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let oracle = client
+            .price_oracle(
+                &String::from_str(&env, "soy"),
+                &Symbol::new(&env, "BRL_USD"),
+            )
+            .unwrap();
+
+        // No feed for the reverse pair
+        assert!(
+            client
+                .try_swap(&addresses.producer, &usd, &brl, &1_000, &0)
+                .is_err()
+        );
+
+        // One hour old is still accepted, one second more is stale
+        env.ledger().set_timestamp(1741564800 + 3_600);
+        client.swap(&addresses.producer, &brl, &usd, &10_000, &0);
+        assert_eq!(client.series_supply(&usd), 505_500);
+        env.ledger().set_timestamp(1741564800 + 3_601);
+        assert!(
+            client
+                .try_swap(&addresses.producer, &brl, &usd, &10_000, &0)
+                .is_err()
+        );
+
+        // The admin can push a swap through at a stated price while the feed is stale,
+        // within 10% of its last quote
+        assert_contract_error(
+            client.try_swap_at_price(&addresses.producer, &brl, &usd, &10_000, &4949, &0),
+            ExtError::PriceDeviation,
+        );
+        assert_contract_error(
+            client.try_swap_at_price(&addresses.producer, &brl, &usd, &10_000, &6051, &0),
+            ExtError::PriceDeviation,
+        );
+        client.swap_at_price(&addresses.producer, &brl, &usd, &10_000, &5000, &0);
+        assert_eq!(env.auths()[0].0, addresses.admin);
+        assert_eq!(client.series_supply(&usd), 510_500);
+
+        // A fresh quote restores normal swaps
+        MockPriceFeedClient::new(&env, &oracle).set_price(
+            &Asset::Other(Symbol::new(&env, "BRL_USD")),
+            &(6000 * 10i128.pow(10)),
+            &env.ledger().timestamp(),
+        );
+        client.swap(&addresses.producer, &brl, &usd, &10_000, &0);
+        assert_eq!(client.series_supply(&usd), 516_500);
+    }
+
+    // Test 104: A rejected mint leaves no series, balances or supply behind
+    #[test]
+    fn test_failed_mint_writes_nothing() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let mut distribution = create_standard_distribution(&addresses);
+        distribution.producer_percent -= 1; // 99.99% in total
+
+        let result = client.try_mint_spot(
+            &addresses.admin,
+            &series_id,
+            &create_spot_metadata(&env, &addresses.producer),
+            &distribution,
+            &1_000_000,
+        );
+        assert!(result.is_err());
+
+        assert!(client.get_series(&series_id).is_none());
+        assert_eq!(client.series_supply(&series_id), 0);
+        assert_eq!(client.total_supply(), 0);
+        assert_eq!(client.balance_of(&addresses.producer), 0);
+        assert_eq!(client.balance_of(&addresses.admin), 0);
+        assert_eq!(client.balance_of(&addresses.storage), 0);
+    }
+
+    // Test 105: Facilities with a confirmation window only confirm inside it, unless overridden
+    #[cfg(all(feature = "batch", feature = "facility-ops"))]
+    #[test]
+    fn test_facility_confirmation_window() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let slc = String::from_str(&env, "SLC");
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+
+        // Business days, 08:00-18:00 UTC
+        let window = ConfirmationWindow {
+            days_mask: 0b001_1111,
+            start_hour: 8,
+            end_hour: 18,
+        };
+        client.set_confirmation_window(&slc, &Some(window.clone()));
+        assert_eq!(client.confirmation_window(&slc), Some(window));
+
+        let monday = 1741564800; // 2025-03-10 00:00 UTC
+        let saturday = monday + 5 * 86_400;
+        let item = vec![&env, (series_id.clone(), 100_000_i128)];
+
+        env.ledger().set_timestamp(monday + 10 * 3_600);
+        client.confirm_delivery_batch(&addresses.storage, &item);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 395_000);
+
+        env.ledger().set_timestamp(monday + 18 * 3_600);
+        assert!(
+            client
+                .try_confirm_delivery_batch(&addresses.storage, &item)
+                .is_err()
+        );
+        env.ledger().set_timestamp(saturday + 10 * 3_600);
+        assert!(
+            client
+                .try_confirm_delivery(&series_id, &addresses.storage)
+                .is_err()
+        );
+
+        // Emergency override lets the weekend confirmation through
+        client.set_confirmation_override(&slc, &true);
+        client.confirm_delivery_batch(&addresses.storage, &item);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 295_000);
+
+        client.set_confirmation_override(&slc, &false);
+        assert!(
+            client
+                .try_confirm_delivery(&series_id, &addresses.storage)
+                .is_err()
+        );
+        env.ledger()
+            .set_timestamp(saturday + 3 * 86_400 + 8 * 3_600); // Tuesday 08:00
+        client.confirm_delivery(&series_id, &addresses.storage);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+    }
+
+    // Test 106: Fertilizer series carry a lot whose bags add up to the quantity
+    #[test]
+    fn test_fertilizer_lot_info() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let distribution = create_standard_distribution(&addresses);
+        let series_id = String::from_str(&env, "CTGNPK-BRL-2025Q1");
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.id = series_id.clone();
+        metadata.asset_type = String::from_str(&env, "fertilizer");
+        metadata.quantity_kg = 50_000;
+
+        // Fertilizer without a lot is rejected
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &distribution,
+                &50_000,
+            ),
+            ContractError::InvalidLotInfo,
+        );
+
+        // Bags that do not add up to the quantity are rejected
+        let mut lot = LotInfo {
+            lot_number: String::from_str(&env, "NPK-2025-0042"),
+            bag_count: 999,
+            bag_kg: 50,
+        };
+        metadata.lot = SeriesLot::Bagged(lot.clone());
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &distribution,
+                &50_000,
+            ),
+            ContractError::InvalidLotInfo,
+        );
+
+        lot.bag_count = 1_000;
+        metadata.lot = SeriesLot::Bagged(lot.clone());
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &50_000,
+        );
+        assert_eq!(
+            client.get_series(&series_id).unwrap().lot,
+            SeriesLot::Bagged(lot)
+        );
+
+        // Grain series may omit the lot
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let grain = client
+            .get_series(&String::from_str(&env, "CTGSoy-BRL-2025Q1"))
+            .unwrap();
+        assert_eq!(grain.lot, SeriesLot::Bulk);
+    }
+
+    // Test 107: Amounts that would overflow i128 fail cleanly instead of wrapping
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_arithmetic_overflow() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = u64::MAX;
+        let distribution = create_standard_distribution(&addresses);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &distribution,
+                &i128::MAX,
+            ),
+            ContractError::ArithmeticOverflow,
+        );
+
+        // The largest series a u64 of kg can back mints normally
+        let near_max = u64::MAX as i128;
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &near_max,
+        );
+        assert_eq!(client.total_supply(), near_max);
+
+        let mut metadata_usd = metadata.clone();
+        metadata_usd.currency = String::from_str(&env, "USD");
+        metadata_usd.quantity_kg = 1_000;
+        metadata_usd.storage_facility = String::from_str(&env, "SLC");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        client.mint_spot(&addresses.admin, &usd, &metadata_usd, &distribution, &1_000);
+
+        // A crafted oracle price overflows the conversion rather than wrapping
+        let oracle = set_fx_price(&env, &client, 5500);
+        MockPriceFeedClient::new(&env, &oracle).set_price(
+            &Asset::Other(Symbol::new(&env, "BRL_USD")),
+            &(i128::MAX / 2),
+            &env.ledger().timestamp(),
+        );
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &series_id, &usd, &1_000, &0),
+            ContractError::ArithmeticOverflow,
+        );
+        assert_contract_error(
+            client.try_swap_at_price(
+                &addresses.producer,
+                &series_id,
+                &usd,
+                &1_000,
+                &i128::MAX,
+                &0,
+            ),
+            ContractError::ArithmeticOverflow,
+        );
+        assert_eq!(client.total_supply(), near_max + 1_000);
+    }
+
+    /// The last invocation stayed within the hinted footprint
+    #[cfg(feature = "insights")]
+    fn assert_within_hint(env: &Env, hint: &FootprintHint) {
+        let used = env.cost_estimate().resources();
+        assert!(used.read_entries <= hint.reads, "{:?}", used);
+        assert!(used.write_entries <= hint.writes, "{:?}", used);
+        assert!(
+            used.instructions as u64 <= hint.instructions_estimate,
+            "{:?}",
+            used
+        );
+    }
+
+    // Test 108: Footprint hints bound what each entry point actually touches
+    #[cfg(all(feature = "insights", feature = "swaps"))]
+    #[test]
+    fn test_footprint_hints() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        let hint = client.footprint_hint(&OpKind::MintSpot);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::MintFuture);
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::Transfer);
+        client.transfer(&addresses.producer, &addresses.third_party, &10_000);
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::TransferSeries);
+        client.transfer_series(&addresses.producer, &addresses.third_party, &brl, &10_000);
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::Burn);
+        client.burn(&addresses.producer, &1_000);
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::BurnSeries);
+        client.burn_series(&addresses.producer, &brl, &1_000);
+        assert_within_hint(&env, &hint);
+
+        let mut metadata_usd = create_spot_metadata(&env, &addresses.producer);
+        metadata_usd.currency = String::from_str(&env, "USD");
+        client.mint_spot(
+            &addresses.admin,
+            &usd,
+            &metadata_usd,
+            &create_standard_distribution(&addresses),
+            &1_000,
+        );
+        set_fx_price(&env, &client, 5500);
+        let hint = client.footprint_hint(&OpKind::Swap);
+        client.swap(&addresses.producer, &brl, &usd, &1_000, &0);
+        assert_within_hint(&env, &hint);
+
+        let hint = client.footprint_hint(&OpKind::ConfirmDelivery);
+        client.confirm_delivery(&future, &addresses.storage);
+        assert_within_hint(&env, &hint);
+    }
+
+    // Test 109: Negative amounts are rejected; zero amounts move nothing
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_negative_amounts_rejected() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        client.transfer(&addresses.producer, &addresses.third_party, &50_000);
+        let victim_balance = client.balance(&addresses.third_party);
+        let supply = client.total_supply();
+
+        // A negative transfer would otherwise pull tokens from the recipient
+        assert_contract_error(
+            client.try_transfer(&addresses.producer, &addresses.third_party, &-50_000),
+            ContractError::InvalidAmount,
+        );
+        assert_contract_error(
+            client.try_transfer_series(&addresses.producer, &addresses.third_party, &brl, &-50_000),
+            ContractError::InvalidAmount,
+        );
+        assert_eq!(client.balance(&addresses.third_party), victim_balance);
+
+        assert_contract_error(
+            client.try_burn(&addresses.producer, &-1),
+            ContractError::InvalidAmount,
+        );
+        assert_contract_error(
+            client.try_burn_series(&addresses.producer, &brl, &-1),
+            ContractError::InvalidAmount,
+        );
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &brl,
+                &create_spot_metadata(&env, &addresses.producer),
+                &create_standard_distribution(&addresses),
+                &-1,
+            ),
+            ContractError::InvalidAmount,
+        );
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &brl, &usd, &-1_000, &0),
+            ContractError::InvalidAmount,
+        );
+        assert_contract_error(
+            client.try_swap_at_price(&addresses.producer, &brl, &usd, &1_000, &-5000, &0),
+            ContractError::InvalidParameter,
+        );
+        assert_eq!(client.total_supply(), supply);
+
+        // Zero is a no-op rather than an error
+        let producer_balance = client.balance(&addresses.producer);
+        client.transfer(&addresses.producer, &addresses.third_party, &0);
+        client.burn(&addresses.producer, &0);
+        assert_eq!(client.balance(&addresses.producer), producer_balance);
+        assert_eq!(client.balance(&addresses.third_party), victim_balance);
+        assert_eq!(client.total_supply(), supply);
+    }
+
+    // Test 110: Mint shares add up to the minted amount, remainder to the producer
+    #[test]
+    fn test_mint_distribution_has_no_dust() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.quantity_kg = 1_010_000;
+        let distribution = create_standard_distribution(&addresses);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let balances_sum = || {
+            client.balance(&addresses.producer)
+                + client.balance(&addresses.admin)
+                + client.balance(&addresses.storage)
+        };
+
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &999_999,
+        );
+        assert_eq!(client.balance(&addresses.producer), 990_001); // 989_999 plus 2 of dust
+        assert_eq!(client.balance(&addresses.admin), 4_999);
+        assert_eq!(client.balance(&addresses.storage), 4_999);
+        assert_eq!(balances_sum(), client.total_supply());
+
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &10_001,
+        );
+        assert_eq!(client.total_supply(), 1_010_000);
+        assert_eq!(balances_sum(), client.total_supply());
+        assert_eq!(
+            client.series_supply(&series_id),
+            client.balance_of_series(&addresses.producer, &series_id)
+                + client.balance_of_series(&addresses.admin, &series_id)
+                + client.balance_of_series(&addresses.storage, &series_id)
+        );
+    }
+
+    // Test 111: Setters report whether a change applied now or was scheduled
+    #[cfg(feature = "supply-controls")]
+    #[test]
+    fn test_setter_results() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let now = 1741564800;
+        env.ledger().set_timestamp(now);
+
+        assert_eq!(
+            client.set_transfer_fee(&addresses.admin, &75),
+            SettingChange {
+                effective: true,
+                effective_at: now,
+                value: 75,
+            }
+        );
+        assert_eq!(client.get_config().unwrap().transfer_fee_percent, 75);
+
+        let scheduled = client.schedule_issuance_cap(&1_000_000);
+        assert_eq!(
+            scheduled,
+            SettingChange {
+                effective: false,
+                effective_at: now + 2 * 24 * 60 * 60,
+                value: 1_000_000,
+            }
+        );
+        assert_eq!(client.monthly_issuance_cap(), 0);
+
+        env.ledger().set_timestamp(scheduled.effective_at);
+        assert_eq!(
+            client.apply_issuance_cap(),
+            SettingChange {
+                effective: true,
+                effective_at: scheduled.effective_at,
+                value: 1_000_000,
+            }
+        );
+        assert_eq!(client.monthly_issuance_cap(), 1_000_000);
+    }
+
+    // Test 112: Replacing a guarantee agent needs all three parties and moves the escrow
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_replace_guarantee_agent() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let metadata = create_future_metadata(&env, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        let new_agent = Address::generate(&env);
+
+        // The incoming agent has not consented
+        let args = (series_id.clone(), new_agent.clone()).into_val(&env);
+        let invoke = MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "replace_guarantee_agent",
+            args,
+            sub_invokes: &[],
+        };
+        env.mock_auths(&[
+            MockAuth {
+                address: &addresses.admin,
+                invoke: &invoke,
+            },
+            MockAuth {
+                address: &addresses.guarantee_agent,
+                invoke: &invoke,
+            },
+        ]);
+        assert!(
+            client
+                .try_replace_guarantee_agent(&series_id, &new_agent)
+                .is_err()
+        );
+
+        env.mock_all_auths();
+        client.replace_guarantee_agent(&series_id, &new_agent);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 3);
+        assert_eq!(auths[0].0, addresses.admin);
+        assert_eq!(auths[1].0, addresses.guarantee_agent);
+        assert_eq!(auths[2].0, new_agent);
+        assert_eq!(
+            client.get_series(&series_id).unwrap().guarantee_agent,
+            Some(new_agent.clone())
+        );
+        assert_eq!(client.agent_escrow(&series_id).unwrap().agent, new_agent);
+        assert_eq!(client.agent_pending_earnings(&addresses.guarantee_agent), 0);
+        assert_eq!(client.agent_pending_earnings(&new_agent), 2_500);
+
+        // The fee now pays out to the new agent, and delivered series are final
+        client.confirm_delivery(&series_id, &addresses.storage);
+        assert_eq!(client.balance_of(&new_agent), 2_500);
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 0);
+        assert_contract_error(
+            client.try_replace_guarantee_agent(&series_id, &addresses.guarantee_agent),
+            ContractError::AlreadyDelivered,
+        );
+    }
+
+    // Test 113: Per-series supplies add up to the total through mints, transfers and burns
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_series_supply_accounting() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let supplies_sum = || client.series_supply(&spot) + client.series_supply(&future);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &500_000,
+        );
+        assert_eq!(supplies_sum(), client.total_supply());
+
+        let info = client.series_info(&future).unwrap();
+        assert_eq!(info.supply, 500_000);
+        assert_eq!(info.locked, 495_000);
+        assert_eq!(client.series_info(&spot).unwrap().locked, 0);
+
+        client.transfer(&addresses.producer, &addresses.third_party, &100_000);
+        client.burn(&addresses.third_party, &10_000);
+        client.burn_series(&addresses.producer, &spot, &50_000);
+        assert_eq!(supplies_sum(), client.total_supply());
+
+        client.confirm_delivery(&future, &addresses.storage);
+        client.burn_series(&addresses.buyer, &future, &95_000);
+        assert_eq!(supplies_sum(), client.total_supply());
+
+        let info = client.series_info(&future).unwrap();
+        assert_eq!(info.locked, 0);
+        assert_eq!(info.supply, client.series_supply(&future));
+    }
+
+    // Test 114: Series can be listed page by page in creation order
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_series_enumeration() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let ids = [
+            "CTGSoy-BRL-S0",
+            "CTGSoy-BRL-S1",
+            "CTGSoy-BRL-S2",
+            "CTGSoy-BRL-S3",
+            "CTGSoy-BRL-S4",
+            "CTGSoy-BRL-S5",
+            "CTGSoy-BRL-S6",
+            "CTGSoy-BRL-S7",
+            "CTGSoy-BRL-S8",
+            "CTGSoy-BRL-S9",
+        ];
+        let distribution = create_standard_distribution(&addresses);
+        // Created out of lexical order to show the listing follows creation
+        for id in ids.iter().rev() {
+            let series_id = String::from_str(&env, id);
+            let mut metadata = create_spot_metadata(&env, &addresses.producer);
+            metadata.id = series_id.clone();
+            client.mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &distribution,
+                &1_000,
+            );
+        }
+        // Topping up an existing series does not list it twice
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.id = String::from_str(&env, ids[9]);
+        client.mint_spot(
+            &addresses.admin,
+            &metadata.id,
+            &metadata,
+            &distribution,
+            &1_000,
+        );
+        assert_eq!(client.get_series_count(), 10);
+
+        let mut start = 0;
+        let mut pages = 0;
+        loop {
+            let page = client.get_series_ids(&start, &3);
+            for (offset, id) in page.iter().enumerate() {
+                let created = ids.len() - 1 - (start as usize + offset);
+                assert_eq!(id, String::from_str(&env, ids[created]));
+            }
+            pages += 1;
+            if page.len() < 3 {
+                assert_eq!(page.len(), 1); // Final short page
+                break;
+            }
+            start += 3;
+        }
+        assert_eq!(pages, 4);
+        assert_eq!(client.get_series_ids(&10, &3).len(), 0);
+        assert_eq!(client.get_series_ids(&0, &u32::MAX).len(), 10);
+    }
+
+    // Test 115: Series stored in the V1 layout are read upgraded and rewritten as V2
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_v1_series_record_upgrade() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let current = create_spot_metadata(&env, &addresses.producer);
+        let series_id = current.id.clone();
+        let v1 = SeriesMetadataV1 {
+            id: current.id.clone(),
+            asset_type: current.asset_type.clone(),
+            currency: current.currency.clone(),
+            delivery_date: current.delivery_date,
+            producer: current.producer.clone(),
+            storage_facility: current.storage_facility.clone(),
+            buyer: None,
+            location: current.location.clone(),
+            quantity_kg: current.quantity_kg,
+            contract_hash: current.contract_hash.clone(),
+            is_future: false,
+            guarantee_agent: None,
+            buyer_acceptance_required: false,
+            late_penalty_bps_per_day: 0,
+            late_penalty_cap_bps: 0,
+            metadata_uri: None,
+        };
+        let key = DataKey::SeriesMetadata(series_id.clone());
+        env.as_contract(&client.address, || {
+            env.storage().instance().set(&key, &v1);
+        });
+
+        let read = client.get_series(&series_id).unwrap();
+        assert_eq!(read.quantity_kg, current.quantity_kg);
+        assert_eq!(read.lot, SeriesLot::Bulk);
+
+        // Reads leave the record alone; the next write stores the V2 layout
+        env.as_contract(&client.address, || {
+            let raw: Val = env.storage().instance().get(&key).unwrap();
+            assert!(SeriesRecord::try_from_val(&env, &raw).is_err());
+        });
+        client.amend_series(
+            &series_id,
+            &MetadataUpdate {
+                location: Some(String::from_str(&env, "FOB Paranagua")),
+                metadata_uri: None,
+            },
+        );
+        env.as_contract(&client.address, || {
+            assert!(!env.storage().instance().has(&key));
+            let record: SeriesRecord = env.storage().persistent().get(&key).unwrap();
+            match record {
+                SeriesRecord::V2(metadata) => {
+                    assert_eq!(metadata.location, String::from_str(&env, "FOB Paranagua"));
+                    assert_eq!(metadata.lot, SeriesLot::Bulk);
+                }
+                SeriesRecord::V1(_) => panic!("record was not upgraded"),
+            }
+        });
+    }
+
+    // Test 116: Confirmations pay the facility operator a capped fee out of the delivery
+    #[cfg(all(feature = "batch", feature = "facility-ops", feature = "insights"))]
+    #[test]
+    fn test_delivery_fee() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_batch_futures(&env, &client, &addresses);
+        let facility = String::from_str(&env, "SLC");
+        let operator = Address::generate(&env);
+        client.set_facility_operator(&facility, &operator);
+        let buyer_of = |id: &str| {
+            client
+                .get_series(&String::from_str(&env, id))
+                .unwrap()
+                .buyer
+                .unwrap()
+        };
+
+        // No fee until one is configured
+        client.confirm_delivery(&String::from_str(&env, "F2"), &addresses.storage);
+        assert_eq!(client.balance_of(&buyer_of("F2")), 99_000);
+        assert_eq!(client.balance_of(&operator), 0);
+
+        assert_contract_error(client.try_set_delivery_fee(&26), ContractError::FeeTooHigh);
+        client.set_delivery_fee(&20);
+
+        client.confirm_delivery_batch(
+            &addresses.storage,
+            &vec![&env, (String::from_str(&env, "F1"), 40_000)],
+        );
+        let (_, _, data) = env.events().all().last().unwrap();
+        let partial: DeliveryEvent = data.into_val(&env);
+        assert_eq!(partial.fee, 80);
+        assert_eq!(client.balance_of(&buyer_of("F1")), 39_920);
+
+        client.confirm_delivery(&String::from_str(&env, "F1"), &addresses.storage);
+        let (_, _, data) = env.events().all().last().unwrap();
+        let confirmed: DeliveryEvent = data.into_val(&env);
+        assert_eq!(confirmed.amount, 59_000);
+        assert_eq!(confirmed.fee, 118);
+        assert_eq!(client.balance_of(&buyer_of("F1")), 98_802);
+        assert_eq!(client.balance_of(&operator), 198);
+
+        // Confirmations under an emergency override are not charged
+        client.set_confirmation_override(&facility, &true);
+        client.confirm_delivery(&String::from_str(&env, "F3"), &addresses.storage);
+        assert_eq!(client.balance_of(&buyer_of("F3")), 99_000);
+        assert_eq!(client.balance_of(&operator), 198);
+    }
+
+    // Test 117: Re-minting cannot overwrite a series; top-ups repeat or omit its metadata
+    #[cfg(feature = "series-admin")]
+    #[test]
+    fn test_remint_cannot_overwrite_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_spot_tokens(&env, &client, &addresses, 998_000);
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let metadata = create_spot_metadata(&env, &addresses.producer);
+        let distribution = create_standard_distribution(&addresses);
+
+        let mut altered = metadata.clone();
+        altered.location = String::from_str(&env, "FOB Paranagua");
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &altered,
+                &distribution,
+                &1_000,
+            ),
+            ContractError::SeriesTermsImmutable,
+        );
+        assert_eq!(client.get_series(&series_id).unwrap(), metadata);
+
+        // Identical metadata, or none at all, tops the series up
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &distribution,
+            &1_000,
+        );
+        client.mint_additional(&addresses.admin, &series_id, &distribution, &1_000);
+        assert_eq!(client.series_supply(&series_id), 1_000_000);
+        assert_eq!(client.get_series(&series_id).unwrap(), metadata);
+
+        assert_contract_error(
+            client.try_mint_additional(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-BRL-2025Q9"),
+                &distribution,
+                &1_000,
+            ),
+            ContractError::SeriesNotFound,
+        );
+    }
+
+    // Test 118: A series never mints more tokens than its certified kg
+    #[cfg(all(feature = "insights", feature = "series-admin"))]
+    #[test]
+    fn test_series_mint_cap() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let distribution = create_standard_distribution(&addresses);
+
+        // Minting exactly to quantity_kg succeeds, one token more does not
+        mint_spot_tokens(&env, &client, &addresses, 600_000);
+        client.mint_additional(&addresses.admin, &series_id, &distribution, &400_000);
+        assert_eq!(client.series_supply(&series_id), 1_000_000);
+        assert_contract_error(
+            client.try_mint_additional(&addresses.admin, &series_id, &distribution, &1),
+            ContractError::SeriesAtCapacity,
+        );
+
+        // Burns do not give the headroom back on their own
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        assert_contract_error(
+            client.try_mint_additional(&addresses.admin, &series_id, &distribution, &1),
+            ContractError::SeriesAtCapacity,
+        );
+
+        // Re-certifying the stock resets headroom to what is not outstanding
+        let outstanding = client.series_supply(&series_id);
+        let certified = outstanding as u64 + 50_000;
+        client.adjust_series_quantity(&series_id, &certified);
+        assert!(has_event(&env, "series_quantity_adjusted"));
+        assert_eq!(
+            client.get_series(&series_id).unwrap().quantity_kg,
+            certified
+        );
+        assert_eq!(
+            client
+                .get_facility(&String::from_str(&env, "AGRARIA"))
+                .unwrap()
+                .backed_kg,
+            certified
+        );
+        client.mint_additional(&addresses.admin, &series_id, &distribution, &50_000);
+        assert_contract_error(
+            client.try_mint_additional(&addresses.admin, &series_id, &distribution, &1),
+            ContractError::SeriesAtCapacity,
+        );
+
+        // Stock cannot be certified below the tokens outstanding
+        assert_contract_error(
+            client.try_adjust_series_quantity(&series_id, &(certified - 1)),
+            ContractError::SeriesAtCapacity,
+        );
+
+        // Futures are capped the same way
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &create_future_metadata(&env, &addresses),
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &500_001,
+            ),
+            ContractError::SeriesAtCapacity,
+        );
+
+        // Bagged series recount their bags, and must stay whole bags
+        let npk = String::from_str(&env, "CTGNPK-BRL-2025Q1");
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.id = npk.clone();
+        metadata.asset_type = String::from_str(&env, "fertilizer");
+        metadata.storage_facility = String::from_str(&env, "SLC");
+        metadata.quantity_kg = 50_000;
+        metadata.lot = SeriesLot::Bagged(LotInfo {
+            lot_number: String::from_str(&env, "NPK-2025-0042"),
+            bag_count: 1_000,
+            bag_kg: 50,
+        });
+        client.mint_spot(&addresses.admin, &npk, &metadata, &distribution, &50_000);
+        assert_contract_error(
+            client.try_adjust_series_quantity(&npk, &60_025),
+            ContractError::InvalidLotInfo,
+        );
+        client.adjust_series_quantity(&npk, &60_000);
+        match client.get_series(&npk).unwrap().lot {
+            SeriesLot::Bagged(lot) => assert_eq!(lot.bag_count, 1_200),
+            SeriesLot::Bulk => panic!("lot lost"),
+        }
+        client.mint_additional(&addresses.admin, &npk, &distribution, &10_000);
+    }
+
+    // Test 119: Admin tooling can page through the facility registry
+    #[cfg(all(feature = "facility-ops", feature = "insights"))]
+    #[test]
+    fn test_list_facilities() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let agraria = String::from_str(&env, "AGRARIA");
+        let slc = String::from_str(&env, "SLC");
+        let coamo = String::from_str(&env, "COAMO");
+        client.set_facility_capacity(&agraria, &2_000_000);
+        client.set_facility_capacity(&slc, &1_000_000);
+        client.set_facility_operator(&coamo, &addresses.storage);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        // Deactivated facilities stay listed but flagged, and take on no new kg
+        client.set_facility_active(&slc, &false);
+        assert!(has_event(&env, "facility_active"));
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-USD-2025Q4"),
+                &create_future_metadata(&env, &addresses),
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &500_000,
+            ),
+            ContractError::FacilityCapacityExceeded,
+        );
+
+        let first = client.list_facilities(&0, &2);
+        assert_eq!(first.len(), 2);
+        assert_eq!(
+            first.get(0).unwrap(),
+            FacilityView {
+                name: agraria.clone(),
+                operator: None,
+                active: true,
+                capacity_kg: 2_000_000,
+                backed_kg: 1_000_000,
+            }
+        );
+        let slc_view = first.get(1).unwrap();
+        assert_eq!(slc_view.name, slc);
+        assert!(!slc_view.active);
+        assert_eq!(slc_view.backed_kg, 0);
+
+        let second = client.list_facilities(&1, &2);
+        assert_eq!(second.len(), 1);
+        assert_eq!(
+            second.get(0).unwrap(),
+            FacilityView {
+                name: coamo.clone(),
+                operator: Some(addresses.storage.clone()),
+                active: true,
+                capacity_kg: 0,
+                backed_kg: 0,
+            }
+        );
+        assert_eq!(client.list_facilities(&2, &2).len(), 0);
+        assert_eq!(client.list_facilities(&0, &0).len(), 0);
+
+        assert_eq!(client.facility(&coamo), second.get(0));
+        assert_eq!(client.facility(&String::from_str(&env, "CARGILL")), None);
+
+        // Reactivating lifts the flag without re-registering the facility
+        client.set_facility_active(&slc, &true);
+        assert!(client.facility(&slc).unwrap().active);
+        assert_eq!(client.list_facilities(&0, &10).len(), 3);
+    }
+
+    // Test 120: Distribution checks cannot be wrapped or routed around the producer
+    #[cfg(feature = "facility-ops")]
+    #[test]
+    fn test_distribution_hardening() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let metadata = create_spot_metadata(&env, &addresses.producer);
+        let split = |producer: u32, platform: u32, storage: u32| Distribution {
+            producer_address: addresses.producer.clone(),
+            storage_address: addresses.storage.clone(),
+            producer_percent: producer,
+            platform_percent: platform,
+            storage_percent: storage,
+        };
+
+        // Shares that would wrap a u32 sum back to 10000
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &split(10000, u32::MAX, 1),
+                &1_000,
+            ),
+            ContractError::InvalidDistribution,
+        );
+        assert_contract_error(
+            client.try_set_facility_fee_recipients(
+                &String::from_str(&env, "AGRARIA"),
+                &vec![
+                    &env,
+                    FeeShare {
+                        recipient: Address::generate(&env),
+                        bps: u32::MAX,
+                    },
+                    FeeShare {
+                        recipient: Address::generate(&env),
+                        bps: 10001,
+                    },
+                ],
+            ),
+            ContractError::InvalidDistribution,
+        );
+
+        // A spot mint always pays the producer something
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &split(0, 0, 10000),
+                &1_000,
+            ),
+            ContractError::InvalidDistribution,
+        );
+
+        // Fees beyond 10% need the admin override
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &split(9000, 500, 500),
+            &1_000,
+        );
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &split(8999, 500, 501),
+                &1_000,
+            ),
+            ContractError::FeeTooHigh,
+        );
+        client.set_high_fee_split_allowed(&true);
+        assert!(has_event(&env, "high_fee_split_allowed"));
+        client.mint_spot(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &split(8000, 1000, 1000),
+            &1_000,
+        );
+        assert_eq!(client.balance_of(&addresses.producer), 900 + 800);
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &split(0, 5000, 5000),
+                &1_000,
+            ),
+            ContractError::InvalidDistribution,
+        );
+
+        client.set_high_fee_split_allowed(&false);
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &split(8000, 1000, 1000),
+                &1_000,
+            ),
+            ContractError::FeeTooHigh,
+        );
+    }
+
+    // Test 121: The admin role moves only when the proposed address accepts it
+    #[test]
+    fn test_admin_transfer() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let new_admin = Address::generate(&env);
+
+        assert_contract_error(client.try_accept_admin(), ContractError::NothingPending);
+
+        // A canceled proposal can no longer be accepted
+        client.propose_admin(&new_admin);
+        assert!(has_event(&env, "admin_proposed"));
+        assert_eq!(client.pending_admin(), Some(new_admin.clone()));
+        client.cancel_admin_proposal();
+        assert!(has_event(&env, "admin_proposal_canceled"));
+        assert_eq!(client.pending_admin(), None);
+        assert_contract_error(client.try_accept_admin(), ContractError::NothingPending);
+        assert_contract_error(
+            client.try_cancel_admin_proposal(),
+            ContractError::NothingPending,
+        );
+
+        // Only the proposed address can accept
+        client.propose_admin(&new_admin);
+        env.mock_auths(&[MockAuth {
+            address: &addresses.third_party,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client.try_accept_admin().is_err());
+        assert_eq!(client.get_config().unwrap().admin, addresses.admin);
+
+        env.mock_auths(&[MockAuth {
+            address: &new_admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        client.accept_admin();
+        assert!(has_event(&env, "admin_transferred"));
+        assert_eq!(client.get_config().unwrap().admin, new_admin);
+        assert_eq!(client.pending_admin(), None);
+
+        // The old admin has lost access to admin setters
+        let set_fee = |admin: &Address| {
+            env.mock_auths(&[MockAuth {
+                address: admin,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "set_transfer_fee",
+                    args: (admin.clone(), 25u32).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }]);
+            client.try_set_transfer_fee(admin, &25)
+        };
+        assert!(set_fee(&addresses.admin).is_err());
+        assert!(set_fee(&new_admin).is_ok());
+    }
+
+    // Test 122: Storage fees follow the storage address once it changes
+    #[test]
+    fn test_set_storage_address() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let slc = Address::generate(&env);
+        let custodian = Address::generate(&env);
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        let accrued = client.balance_of(&addresses.storage);
+        assert!(accrued > 5_000);
+
+        // Without a sweep the old address keeps what it earned
+        client.set_storage_address(&slc, &false);
+        assert!(has_event(&env, "storage_address_changed"));
+        assert_eq!(client.get_config().unwrap().storage_address, slc);
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        assert_eq!(client.balance_of(&addresses.storage), accrued);
+        let slc_fees = client.balance_of(&slc);
+        assert_eq!(slc_fees, accrued - 5_000);
+
+        // A sweep needs the outgoing address's consent and moves its whole balance
+        let args = (custodian.clone(), true).into_val(&env);
+        env.mock_auths(&[MockAuth {
+            address: &addresses.admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "set_storage_address",
+                args,
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client.try_set_storage_address(&custodian, &true).is_err());
+
+        env.mock_all_auths();
+        client.set_storage_address(&custodian, &true);
+        assert_eq!(env.auths().get(1).unwrap().0, slc);
+        assert_eq!(client.balance_of(&slc), 0);
+        assert_eq!(client.balance_of_series(&custodian, &series_id), slc_fees);
+        assert_eq!(client.balance_of(&addresses.storage), accrued);
+    }
+
+    // Test 123: Burn, platform and storage fees are adjustable within the 5% cap
+    #[test]
+    fn test_fee_setters() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        assert_contract_error(
+            client.try_set_burn_fee(&addresses.admin, &501),
+            ContractError::FeeTooHigh,
+        );
+        assert_contract_error(
+            client.try_set_platform_fee(&addresses.admin, &501),
+            ContractError::FeeTooHigh,
+        );
+        assert_contract_error(
+            client.try_set_storage_fee(&addresses.admin, &501),
+            ContractError::FeeTooHigh,
+        );
+
+        // 1% burn fee, split 3:1 between platform and storage
+        client.set_burn_fee(&addresses.admin, &100);
+        let (_, _, data) = env.events().all().last().unwrap();
+        let change: (u32, u32) = data.into_val(&env);
+        assert_eq!(change, (50, 100));
+        client.set_platform_fee(&addresses.admin, &300);
+        assert!(has_event(&env, "platform_fee_changed"));
+        client.set_storage_fee(&addresses.admin, &100);
+        assert!(has_event(&env, "storage_fee_changed"));
+        client.set_transfer_fee(&addresses.admin, &25);
+        let (_, data) = last_event(&env, "transfer_fee_changed");
+        let change: (u32, u32) = data.into_val(&env);
+        assert_eq!(change, (0, 25));
+        client.set_transfer_fee(&addresses.admin, &0);
+
+        let admin_before = client.balance_of(&addresses.admin);
+        let storage_before = client.balance_of(&addresses.storage);
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        assert_eq!(client.balance_of(&addresses.admin), admin_before + 750);
+        assert_eq!(client.balance_of(&addresses.storage), storage_before + 250);
+        assert_eq!(client.total_supply(), 1_000_000 - 99_000);
+
+        // With both weights at zero the fee splits evenly again
+        client.set_platform_fee(&addresses.admin, &0);
+        client.set_storage_fee(&addresses.admin, &0);
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        assert_eq!(client.balance_of(&addresses.admin), admin_before + 1_250);
+        assert_eq!(client.balance_of(&addresses.storage), storage_before + 750);
+    }
+
+    /// The public views agree with the raw storage counters
+    #[cfg(all(feature = "debug-views", feature = "insights", feature = "insurance"))]
+    fn assert_views_match_counters(client: &ContangoTokenClient, addresses: &TestAddresses) {
+        let counters = client.debug_counters();
+        assert_eq!(client.total_supply(), counters.total_supply);
+        assert_eq!(client.insurance_buffer(), counters.insurance_buffer);
+        assert_eq!(client.balance(&addresses.admin), counters.admin_balance);
+        assert_eq!(client.balance(&addresses.storage), counters.storage_balance);
+        assert_eq!(client.get_series_count(), counters.series_outstanding.len());
+
+        let (mut outstanding, mut locked, mut escrowed) = (0, 0, 0);
+        for (series_id, supply) in counters.series_outstanding.iter() {
+            let info = client.series_info(&series_id).unwrap();
+            assert_eq!(info.supply, supply);
+            assert_eq!(client.series_supply(&series_id), supply);
+            outstanding += supply;
+            locked += info.locked;
+            escrowed += client
+                .agent_escrow(&series_id)
+                .map_or(0, |escrow| escrow.amount);
+        }
+        assert_eq!(outstanding, counters.total_supply);
+        assert_eq!(locked, counters.total_locked);
+        assert_eq!(escrowed, counters.total_escrowed);
+    }
+
+    // Test 124: Randomized scenarios keep the public views in line with raw storage
+    #[cfg(all(feature = "debug-views", feature = "insights", feature = "insurance"))]
+    #[test]
+    fn test_views_match_debug_counters() {
+        for seed in [7u64, 1_234, 99_991] {
+            let (env, client, addresses) = setup_test_env();
+            env.mock_all_auths();
+            client.set_insurance_reserve(&10);
+
+            let spot_ids = [
+                "CTGSoy-BRL-2025Q1",
+                "CTGCorn-BRL-2025Q2",
+                "CTGCafe-BRL-2025Q3",
+            ];
+            let future_ids = ["CTGSoy-USD-2025Q4", "CTGCorn-USD-2026Q1"];
+            let distribution = create_standard_distribution(&addresses);
+
+            // Deterministic LCG so failures replay
+            let mut state = seed;
+            let mut next = |bound: u64| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (state >> 33) % bound
+            };
+
+            for _ in 0..40 {
+                let op = next(6);
+                let amount = next(50_000) as i128 + 1;
+                let spot = String::from_str(&env, spot_ids[next(3) as usize]);
+                let future = String::from_str(&env, future_ids[next(2) as usize]);
+                let _ = match op {
+                    0 => {
+                        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+                        metadata.id = spot.clone();
+                        client
+                            .try_mint_spot(
+                                &addresses.admin,
+                                &spot,
+                                &metadata,
+                                &distribution,
+                                &amount,
+                            )
+                            .map(|_| ())
+                            .map_err(|_| ())
+                    }
+                    1 => client
+                        .try_mint_future(
+                            &addresses.admin,
+                            &future,
+                            &create_future_metadata(&env, &addresses),
+                            &addresses.buyer,
+                            &None,
+                            &Some(addresses.guarantee_agent.clone()),
+                            &amount,
+                        )
+                        .map(|_| ())
+                        .map_err(|_| ()),
+                    2 => client
+                        .try_transfer(&addresses.producer, &addresses.third_party, &amount)
+                        .map(|_| ())
+                        .map_err(|_| ()),
+                    3 => client
+                        .try_burn_series(&addresses.producer, &spot, &amount)
+                        .map(|_| ())
+                        .map_err(|_| ()),
+                    4 => client
+                        .try_burn_series(&addresses.third_party, &spot, &amount)
+                        .map(|_| ())
+                        .map_err(|_| ()),
+                    _ => client
+                        .try_confirm_delivery(&future, &addresses.storage)
+                        .map(|_| ())
+                        .map_err(|_| ()),
+                };
+                assert_views_match_counters(&client, &addresses);
+            }
+            assert!(client.total_supply() > 0);
+        }
+    }
+
+    // Test 125: A paused contract halts trading but keeps its views
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_pause_and_unpause() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let future_metadata = create_future_metadata(&env, &addresses);
+        let agent = Some(addresses.guarantee_agent.clone());
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &future_metadata,
+            &addresses.buyer,
+            &None,
+            &agent,
+            &100_000,
+        );
+        client.burn_series(&addresses.producer, &usd, &50_000); // room to swap into
+
+        client.pause(&addresses.admin, &ReasonCode::OperationalError, &None);
+        assert!(has_event(&env, "paused"));
+        assert!(client.is_paused());
+
+        let metadata = create_spot_metadata(&env, &addresses.producer);
+        let distribution = create_standard_distribution(&addresses);
+        let (producer, buyer) = (&addresses.producer, &addresses.buyer);
+        assert_contract_error(
+            client.try_transfer(producer, buyer, &1_000),
+            ContractError::ContractPaused,
+        );
+        assert_contract_error(
+            client.try_burn(producer, &1_000),
+            ContractError::ContractPaused,
+        );
+        assert_contract_error(
+            client.try_swap(producer, &brl, &usd, &1_000, &0),
+            ContractError::ContractPaused,
+        );
+        assert_contract_error(
+            client.try_mint_spot(&addresses.admin, &brl, &metadata, &distribution, &1_000),
+            ContractError::ContractPaused,
+        );
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &future,
+                &future_metadata,
+                buyer,
+                &None,
+                &agent,
+                &1_000,
+            ),
+            ContractError::ContractPaused,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery(&future, &addresses.storage),
+            ContractError::ContractPaused,
+        );
+
+        // Views keep working
+        let balance = client.balance_of(producer);
+        assert!(balance > 0);
+        assert!(client.get_series(&brl).is_some());
+        assert_eq!(
+            client.total_supply(),
+            1_000_000 + 500_000 + 100_000 - 49_750
+        );
+
+        client.unpause(&addresses.admin);
+        assert!(has_event(&env, "unpaused"));
+        assert!(!client.is_paused());
+
+        client.transfer(producer, buyer, &1_000);
+        client.burn(producer, &1_000);
+        client.swap(producer, &brl, &usd, &1_000, &0);
+        client.mint_spot(&addresses.admin, &brl, &metadata, &distribution, &0);
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &future_metadata,
+            buyer,
+            &None,
+            &agent,
+            &0,
+        );
+        client.confirm_delivery(&future, &addresses.storage);
+        assert_eq!(client.locked_balance_of(buyer), 0);
+    }
+
+    // Test 126: Batches run in input order and handle repeated keys by a fixed rule
+    #[cfg(all(
+        feature = "batch",
+        feature = "facility-ops",
+        feature = "insights",
+        feature = "swaps"
+    ))]
+    #[test]
+    fn test_batch_order_and_duplicates() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+
+        // Netting merges repeated series and settles in order of first appearance,
+        // not in the key order BRL < USD
+        client.net_settle(
+            &addresses.producer,
+            &addresses.third_party,
+            &vec![
+                &env,
+                (usd.clone(), 1_000_i128),
+                (brl.clone(), 2_000_i128),
+                (usd.clone(), -400_i128),
+            ],
+        );
+        assert_eq!(
+            client.held_series(&addresses.third_party),
+            vec![&env, usd.clone(), brl.clone()]
+        );
+        assert_eq!(client.balance_of_series(&addresses.third_party, &usd), 600);
+
+        // A balance only reached mid-batch is usable by the later ops, not the earlier
+        let payee = Address::generate(&env);
+        let forward = vec![
+            &env,
+            SimOp::Transfer(addresses.producer.clone(), payee.clone(), 1_000),
+            SimOp::Transfer(payee.clone(), addresses.buyer.clone(), 1_000),
+        ];
+        let backward = vec![&env, forward.get(1).unwrap(), forward.get(0).unwrap()];
+        assert_eq!(
+            client.simulate(&forward).results,
+            vec![&env, SimResult::Ok, SimResult::Ok]
+        );
+        assert_eq!(
+            client.simulate(&backward).results,
+            vec![&env, SimResult::InsufficientBalance, SimResult::Ok]
+        );
+
+        // Delivery batches reject a series listed twice
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery_batch(
+                &addresses.storage,
+                &vec![
+                    &env,
+                    (future.clone(), 1_000_i128),
+                    (future.clone(), 1_000_i128),
+                ],
+            ),
+            ContractError::DuplicateItem,
+        );
+
+        // Fee routing rejects a recipient listed twice
+        let silo = Address::generate(&env);
+        assert_contract_error(
+            client.try_set_facility_fee_recipients(
+                &String::from_str(&env, "AGRARIA"),
+                &vec![
+                    &env,
+                    FeeShare {
+                        recipient: silo.clone(),
+                        bps: 5000,
+                    },
+                    FeeShare {
+                        recipient: silo,
+                        bps: 5000,
+                    },
+                ],
+            ),
+            ContractError::DuplicateItem,
+        );
+    }
+
+    // Test 127: A frozen account can neither send nor receive, but deliveries still unlock
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_freeze_account() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        client.transfer(&addresses.producer, &addresses.third_party, &10_000);
+
+        // Receiving side
+        client.freeze_account(&addresses.third_party, &ReasonCode::Sanctions, &None);
+        assert!(has_event(&env, "account_frozen"));
+        assert!(client.is_frozen(&addresses.third_party));
+        assert_contract_error(
+            client.try_transfer(&addresses.producer, &addresses.third_party, &1_000),
+            ExtError::AccountFrozen,
+        );
+
+        // Sending side
+        assert_contract_error(
+            client.try_transfer(&addresses.third_party, &addresses.producer, &1_000),
+            ExtError::AccountFrozen,
+        );
+        assert_contract_error(
+            client.try_burn_series(&addresses.third_party, &brl, &1_000),
+            ExtError::AccountFrozen,
+        );
+        assert_contract_error(
+            client.try_burn(&addresses.third_party, &1_000),
+            ExtError::AccountFrozen,
+        );
+        assert_eq!(client.balance_of(&addresses.third_party), 10_000);
+
+        // A frozen producer cannot be paid a mint distribution or swap out
+        client.freeze_account(&addresses.producer, &ReasonCode::Sanctions, &None);
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &brl,
+                &create_spot_metadata(&env, &addresses.producer),
+                &create_standard_distribution(&addresses),
+                &0,
+            ),
+            ExtError::AccountFrozen,
+        );
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &brl, &usd, &1_000, &0),
+            ExtError::AccountFrozen,
+        );
+
+        // Delivery unlocks into a frozen buyer, who then cannot move the tokens
+        client.freeze_account(&addresses.buyer, &ReasonCode::Sanctions, &None);
+        client.confirm_delivery(&future, &addresses.storage);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+        assert!(client.balance_of(&addresses.buyer) > 0);
+        assert_contract_error(
+            client.try_transfer(&addresses.buyer, &addresses.third_party, &1_000),
+            ExtError::AccountFrozen,
+        );
+
+        client.unfreeze_account(&addresses.third_party);
+        assert!(has_event(&env, "account_unfrozen"));
+        assert!(!client.is_frozen(&addresses.third_party));
+        client.unfreeze_account(&addresses.buyer);
+        client.transfer(&addresses.buyer, &addresses.third_party, &1_000);
+        client.transfer(&addresses.third_party, &addresses.buyer, &1_000);
+    }
+
+    // Test 128: The admin can claw back spendable and locked tokens
+    #[cfg(all(feature = "insights", feature = "recovery"))]
+    #[test]
+    fn test_clawback() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let supply = client.total_supply();
+
+        client.clawback(&addresses.producer, &spot, &90_000);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        let target: Address = topics.get(2).unwrap().into_val(&env);
+        let (amount, locked): (i128, bool) = data.into_val(&env);
+        assert_eq!(
+            (target, amount, locked),
+            (addresses.producer.clone(), 90_000, false)
+        );
+        assert_eq!(client.balance_of(&addresses.producer), 900_000);
+        assert_eq!(client.total_supply(), supply - 90_000);
+        assert_eq!(client.series_supply(&spot), 910_000);
+        assert_eq!(
+            client.burn_breakdown().get(BurnKind::Clawback),
+            Some(90_000)
+        );
+
+        // Holding less than the amount fails without touching anything
+        assert_contract_error(
+            client.try_clawback(&addresses.producer, &spot, &900_001),
+            ContractError::InsufficientBalance,
+        );
+        assert_contract_error(
+            client.try_clawback(&addresses.buyer, &spot, &1),
+            ContractError::InsufficientBalance,
+        );
+
+        // Locked future tokens come from the series' buyer only
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 99_000);
+        assert_contract_error(
+            client.try_clawback_locked(&addresses.producer, &future, &1),
+            ContractError::NoLockedTokens,
+        );
+        assert_contract_error(
+            client.try_clawback_locked(&addresses.buyer, &future, &99_001),
+            ContractError::InsufficientBalance,
+        );
+        client.clawback_locked(&addresses.buyer, &future, &40_000);
+        assert!(has_event(&env, "clawback"));
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 59_000);
+        assert_eq!(client.series_info(&future).unwrap().locked, 59_000);
+        assert_eq!(client.total_supply(), supply - 130_000);
+
+        // Only the admin can claw back
+        env.mock_auths(&[]);
+        assert!(client.try_clawback(&addresses.producer, &spot, &1).is_err());
+    }
+
+    // Test 129: Minting can be switched off for good behind a timelock
+    #[cfg(all(
+        feature = "insights",
+        feature = "producers",
+        feature = "series-admin",
+        feature = "supply-controls",
+        feature = "swaps"
+    ))]
+    #[test]
+    fn test_disable_minting_forever() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        mint_penalty_future(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.set_facility_operator(&String::from_str(&env, "AGRARIA"), &addresses.storage);
+        client.set_producer_quota(&addresses.producer, &1_000_000);
+
+        // Nothing to apply until scheduled, and the timelock must run out
+        assert_contract_error(
+            client.try_disable_minting_forever(),
+            ContractError::NothingPending,
+        );
+        let now = 1751328000 + 10 * 86_400; // 10 days past the future's delivery date
+        env.ledger().set_timestamp(now);
+        let eta = client.schedule_minting_shutdown();
+        assert_eq!(eta, now + MINTING_SHUTDOWN_DELAY_SECS);
+        assert!(has_event(&env, "minting_shutdown_scheduled"));
+        assert_contract_error(
+            client.try_schedule_minting_shutdown(),
+            ContractError::AlreadyPending,
+        );
+        assert_contract_error(
+            client.try_disable_minting_forever(),
+            ContractError::TimelockNotExpired,
+        );
+
+        // A scheduled shutdown can still be called off
+        client.cancel_minting_shutdown();
+        assert!(has_event(&env, "minting_shutdown_canceled"));
+        assert_contract_error(
+            client.try_cancel_minting_shutdown(),
+            ContractError::NothingPending,
+        );
+        let eta = client.schedule_minting_shutdown();
+        env.ledger().set_timestamp(eta);
+        assert!(client.minting_enabled());
+        client.disable_minting_forever();
+        assert!(has_event(&env, "minting_disabled_forever"));
+        assert!(!client.minting_enabled());
+
+        // Every mint path is closed
+        let mut metadata = create_spot_metadata(&env, &addresses.producer);
+        metadata.id = String::from_str(&env, "CTGSoy-BRL-2026Q1");
+        assert_contract_error(
+            client.try_mint_spot(
+                &addresses.admin,
+                &metadata.id,
+                &metadata,
+                &create_standard_distribution(&addresses),
+                &1_000,
+            ),
+            ExtError::MintingDisabled,
+        );
+        assert_contract_error(
+            client.try_mint_additional(
+                &addresses.admin,
+                &brl,
+                &create_standard_distribution(&addresses),
+                &1_000,
+            ),
+            ExtError::MintingDisabled,
+        );
+        assert_contract_error(
+            client.try_producer_mint_spot(
+                &addresses.producer,
+                &metadata.id,
+                &metadata,
+                &create_standard_distribution(&addresses),
+                &1_000,
+            ),
+            ExtError::MintingDisabled,
+        );
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &String::from_str(&env, "CTGSoy-USD-2026Q4"),
+                &create_future_metadata(&env, &addresses),
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &1_000,
+            ),
+            ExtError::MintingDisabled,
+        );
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &brl, &usd, &1_000, &0),
+            ExtError::MintingDisabled,
+        );
+
+        // Holders can still exit, and a late delivery no longer mints a penalty
+        client.transfer(&addresses.producer, &addresses.third_party, &10_000);
+        client.burn(&addresses.third_party, &10_000);
+        assert_eq!(client.accrued_penalty(&future), 0);
+        let supply = client.total_supply();
+        client.confirm_delivery(&future, &addresses.storage);
+        assert_eq!(client.balance_of(&addresses.buyer), 495_000);
+        assert_eq!(client.total_supply(), supply);
+
+        // There is no way back
+        assert_contract_error(
+            client.try_schedule_minting_shutdown(),
+            ExtError::MintingDisabled,
+        );
+        assert!(!client.minting_enabled());
+    }
+
+    // Test 130: Roles split minting, fees, pausing and delivery between addresses
+    #[cfg(all(feature = "series-admin", feature = "swaps"))]
+    #[test]
+    fn test_roles() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let minter = Address::generate(&env);
+        let fee_manager = Address::generate(&env);
+        let pauser = Address::generate(&env);
+        let validator = Address::generate(&env);
+        client.grant_role(&Role::Minter, &minter);
+        assert!(has_event(&env, "role_granted"));
+        client.grant_role(&Role::FeeManager, &fee_manager);
+        client.grant_role(&Role::Pauser, &pauser);
+        client.grant_role(&Role::DeliveryValidator, &validator);
+
+        assert!(client.has_role(&Role::Minter, &minter));
+        assert!(!client.has_role(&Role::FeeManager, &minter));
+        for role in [
+            Role::Minter,
+            Role::FeeManager,
+            Role::Pauser,
+            Role::DeliveryValidator,
+        ] {
+            assert!(client.has_role(&role, &addresses.admin));
+        }
+
+        // A minter can mint but not change fees
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        client.mint_spot(
+            &minter,
+            &series_id,
+            &create_spot_metadata(&env, &addresses.producer),
+            &create_standard_distribution(&addresses),
+            &100_000,
+        );
+        assert_eq!(client.balance_of(&addresses.producer), 99_000);
+        assert_contract_error(
+            client.try_set_transfer_fee(&minter, &25),
+            ContractError::Unauthorized,
+        );
+        assert_contract_error(
+            client.try_pause(&minter, &ReasonCode::OperationalError, &None),
+            ContractError::Unauthorized,
+        );
+
+        // A fee manager can change fees but not mint
+        assert_contract_error(
+            client.try_mint_additional(
+                &fee_manager,
+                &series_id,
+                &create_standard_distribution(&addresses),
+                &1_000,
+            ),
+            ContractError::Unauthorized,
+        );
+        client.set_transfer_fee(&fee_manager, &25);
+        client.set_burn_fee(&fee_manager, &10);
+        assert_eq!(client.get_config().unwrap().transfer_fee_percent, 25);
+
+        // Only a pauser can pause and unpause, and halt swaps
+        client.pause(&pauser, &ReasonCode::OperationalError, &None);
+        assert!(client.is_paused());
+        client.unpause(&pauser);
+        assert_contract_error(
+            client.try_set_swaps_enabled(&minter, &false),
+            ContractError::Unauthorized,
+        );
+        client.set_swaps_enabled(&pauser, &false);
+        assert!(!client.swaps_enabled());
+        client.set_swaps_enabled(&pauser, &true);
+
+        // Deliveries need a validator; any other address is turned away
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &minter,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery(&future, &addresses.third_party),
+            ContractError::Unauthorized,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery(&future, &minter),
+            ContractError::Unauthorized,
+        );
+        client.confirm_delivery(&future, &validator);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+
+        // Revoked roles stop working at once
+        client.revoke_role(&Role::Minter, &minter);
+        assert!(has_event(&env, "role_revoked"));
+        assert!(!client.has_role(&Role::Minter, &minter));
+        assert_contract_error(
+            client.try_mint_additional(
+                &minter,
+                &series_id,
+                &create_standard_distribution(&addresses),
+                &1_000,
+            ),
+            ContractError::Unauthorized,
+        );
+
+        // Only the admin manages roles
+        env.mock_auths(&[]);
+        assert!(client.try_grant_role(&Role::Minter, &minter).is_err());
+        assert!(client.try_revoke_role(&Role::Pauser, &pauser).is_err());
+    }
+
+    // Test 131: Only the storage address, the guarantee agent or a registered validator confirm
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_delivery_validators() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let first = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let second = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        let second_buyer = Address::generate(&env);
+        for (series_id, buyer) in [(&first, &addresses.buyer), (&second, &second_buyer)] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.mint_future(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            );
+        }
+
+        // The buyer cannot confirm their own delivery, even once registered
+        assert_contract_error(
+            client.try_confirm_delivery(&first, &addresses.buyer),
+            ContractError::Unauthorized,
+        );
+        let slc = String::from_str(&env, "SLC");
+        client.set_facility_validator(&slc, &addresses.buyer, &true);
+        assert_contract_error(
+            client.try_confirm_delivery(&first, &addresses.buyer),
+            ContractError::Unauthorized,
+        );
+
+        // A validator registered at another facility is turned away
+        let validator = Address::generate(&env);
+        client.set_facility_validator(&String::from_str(&env, "AGRARIA"), &validator, &true);
+        assert!(!client.is_delivery_validator(&slc, &validator));
+        assert_contract_error(
+            client.try_confirm_delivery(&first, &validator),
+            ContractError::Unauthorized,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery_batch(&validator, &vec![&env, (first.clone(), 1_000)]),
+            ContractError::Unauthorized,
+        );
+
+        // Registered at the series' facility, the same address can confirm
+        client.set_facility_validator(&slc, &validator, &true);
+        assert!(has_event(&env, "facility_validator"));
+        assert!(client.is_delivery_validator(&slc, &validator));
+        client.confirm_delivery(&first, &validator);
+        assert_eq!(client.series_status(&first), Some(SeriesStatus::Delivered));
+
+        // The series' guarantee agent can confirm too; a withdrawn validator cannot
+        client.set_facility_validator(&slc, &validator, &false);
+        assert_contract_error(
+            client.try_confirm_delivery(&second, &validator),
+            ContractError::Unauthorized,
+        );
+        client.confirm_delivery(&second, &addresses.guarantee_agent);
+        assert_eq!(client.locked_balance_of(&second_buyer), 0);
+    }
+
+    // Test 132: Locked tokens are tracked per series, so one delivery leaves other futures locked
+    #[cfg(all(feature = "batch", feature = "insights"))]
+    #[test]
+    fn test_locked_balance_per_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let first = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let second = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        for (series_id, amount) in [(&first, 100_000), (&second, 200_000)] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.mint_future(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &amount,
+            );
+        }
+        let buyer = &addresses.buyer;
+        assert_eq!(client.locked_balance_of_series(buyer, &first), 99_000);
+        assert_eq!(client.locked_balance_of_series(buyer, &second), 198_000);
+        assert_eq!(client.locked_balance_of(buyer), 297_000);
+        assert_eq!(
+            client.locked_balance_of_series(&addresses.third_party, &first),
+            0
+        );
+
+        client.confirm_delivery(&first, &addresses.storage);
+        assert_eq!(client.locked_balance_of_series(buyer, &first), 0);
+        assert_eq!(client.locked_balance_of_series(buyer, &second), 198_000);
+        assert_eq!(client.locked_balance_of(buyer), 198_000);
+        assert_eq!(client.balance_of(buyer), 99_000);
+        assert_eq!(client.series_status(&first), Some(SeriesStatus::Delivered));
+        assert_eq!(
+            client.series_status(&second),
+            Some(SeriesStatus::DeliveryPending)
+        );
+
+        // A batch item cannot release more than its own series holds
+        assert_contract_error(
+            client.try_confirm_delivery_batch(
+                &addresses.storage,
+                &vec![&env, (second.clone(), 198_001)],
+            ),
+            ContractError::InsufficientBalance,
+        );
+
+        // A future locked before per-series tracking falls back to the buyer's total
+        env.as_contract(&client.address, || {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::SeriesLocked(second.clone()));
+        });
+        assert_eq!(client.locked_balance_of_series(buyer, &second), 198_000);
+        client.confirm_delivery(&second, &addresses.storage);
+        assert_eq!(client.locked_balance_of(buyer), 0);
+        assert_eq!(client.balance_of(buyer), 297_000);
+    }
+
+    // Test 133: An undelivered future can be declared defaulted once its grace period ends
+    #[cfg(feature = "defaults")]
+    #[test]
+    fn test_declare_default() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let first = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let second = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        let second_buyer = Address::generate(&env);
+        for (series_id, buyer) in [(&first, &addresses.buyer), (&second, &second_buyer)] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.mint_future(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            );
+        }
+        let delivery_date = 1751328000;
+        let grace = client.get_config().unwrap().default_grace_secs;
+
+        // Not before the delivery date plus the grace period
+        env.ledger().set_timestamp(delivery_date + grace);
+        assert_contract_error(
+            client.try_declare_default(&addresses.guarantee_agent, &first),
+            ContractError::TimelockNotExpired,
+        );
+
+        // Only the series' guarantee agent or the admin
+        env.ledger().set_timestamp(delivery_date + grace + 1);
+        assert_contract_error(
+            client.try_declare_default(&addresses.buyer, &first),
+            ContractError::Unauthorized,
+        );
+        assert_contract_error(
+            client.try_declare_default(&addresses.third_party, &first),
+            ContractError::Unauthorized,
+        );
+
+        // Locked tokens go back to the buyer, along with the escrowed guarantee fee
+        client.declare_default(&addresses.guarantee_agent, &first);
+        let (_, _, data) = env.events().all().last().unwrap();
+        let (recipient, released, compensation): (Address, i128, i128) = data.into_val(&env);
+        assert_eq!(
+            (recipient, released, compensation),
+            (addresses.buyer.clone(), 99_000, 500)
+        );
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+        assert_eq!(client.balance_of(&addresses.buyer), 99_500);
+        assert_eq!(client.series_status(&first), Some(SeriesStatus::Defaulted));
+        assert_contract_error(
+            client.try_confirm_delivery(&first, &addresses.storage),
+            ContractError::SeriesDefaulted,
+        );
+        assert_contract_error(
+            client.try_declare_default(&addresses.admin, &first),
+            ContractError::SeriesDefaulted,
+        );
+
+        // Under the settlement flag the agent takes the locked tokens instead
+        client.set_default_policy(&0, &true);
+        assert!(has_event(&env, "default_policy_changed"));
+        client.declare_default(&addresses.admin, &second);
+        assert!(has_event(&env, "default_declared"));
+        assert_eq!(client.locked_balance_of(&second_buyer), 0);
+        assert_eq!(client.balance_of(&second_buyer), 500);
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 99_000);
+    }
+
+    // Test 134: Cancelling a future burns the buyer's locked tokens and retires the series
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_cancel_future() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let metadata = create_future_metadata(&env, &addresses);
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let slc = String::from_str(&env, "SLC");
+        assert_eq!(client.get_facility(&slc).unwrap().backed_kg, 500_000);
+
+        // The buyer needs the admin or the series' guarantee agent on board
+        assert_contract_error(
+            client.try_cancel_future(&addresses.third_party, &series_id),
+            ContractError::Unauthorized,
+        );
+
+        client.cancel_future(&addresses.guarantee_agent, &series_id);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 2);
+        for signer in [&addresses.buyer, &addresses.guarantee_agent] {
+            assert!(auths.iter().any(|(address, _)| address == signer));
+        }
+        let (_, _, data) = env.events().all().last().unwrap();
+        let (buyer, burned): (Address, i128) = data.into_val(&env);
+        assert_eq!((buyer, burned), (addresses.buyer.clone(), 99_000));
+
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+        assert_eq!(client.total_supply(), 1_000);
+        assert_eq!(
+            client.burn_breakdown().get(BurnKind::Cancellation),
+            Some(99_000)
+        );
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::Cancelled)
+        );
+        assert_eq!(client.get_facility(&slc).unwrap().backed_kg, 0);
+
+        // Fees paid at mint stay put; the escrowed guarantee fee goes to the agent
+        assert_eq!(client.balance_of(&addresses.admin), 500);
+        assert_eq!(client.balance_of(&addresses.guarantee_agent), 500);
+        assert_eq!(client.agent_pending_earnings(&addresses.guarantee_agent), 0);
+
+        // The series ID cannot be minted again
+        assert_contract_error(
+            client.try_mint_future(
+                &addresses.admin,
+                &series_id,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            ),
+            ExtError::SeriesCancelled,
+        );
+        assert_contract_error(
+            client.try_cancel_future(&addresses.admin, &series_id),
+            ExtError::SeriesCancelled,
+        );
+    }
+
+    // Test 135: A cancelled future can no longer be delivered
+    #[cfg(all(feature = "batch", feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_delivery_after_cancellation() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        client.cancel_future(&addresses.admin, &series_id);
+
+        assert_contract_error(
+            client.try_confirm_delivery(&series_id, &addresses.storage),
+            ExtError::SeriesCancelled,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery_batch(
+                &addresses.storage,
+                &vec![&env, (series_id.clone(), 1)],
+            ),
+            ExtError::SeriesCancelled,
+        );
+        let report = client.simulate(&vec![&env, SimOp::ConfirmDelivery(series_id.clone())]);
+        assert_eq!(report.results.get(0), Some(SimResult::SeriesCancelled));
+    }
+
+    // Test 136: Futures must be dated ahead and expire undelivered after the grace period
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_series_expiry() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let spot = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let metadata = create_future_metadata(&env, &addresses);
+        let delivery_date = metadata.delivery_date;
+        let mint = |amount: i128| {
+            client.try_mint_future(
+                &addresses.admin,
+                &future,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &amount,
+            )
+        };
+
+        // The delivery date must lie strictly ahead
+        env.ledger().set_timestamp(delivery_date);
+        assert_contract_error(mint(100_000), ContractError::InvalidExpiration);
+        env.ledger().set_timestamp(delivery_date - 1);
+        assert!(mint(100_000).is_ok());
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let admin = &addresses.admin;
+        assert_eq!(client.balance_of_series(admin, &future), 500);
+        assert_eq!(client.balance_of_series(admin, &spot), 5_000);
+
+        // Not expired up to the end of the grace period
+        let grace = client.get_config().unwrap().default_grace_secs;
+        env.ledger().set_timestamp(delivery_date + grace);
+        assert!(!client.is_expired(&future));
+        assert!(!client.series_info(&future).unwrap().expired);
+        client.transfer_series(admin, &addresses.third_party, &future, &100);
+
+        // One second later the future's tokens stop moving
+        env.ledger().set_timestamp(delivery_date + grace + 1);
+        assert!(client.is_expired(&future));
+        assert!(client.series_info(&future).unwrap().expired);
+        assert!(!client.is_expired(&spot));
+        assert_contract_error(
+            client.try_transfer_series(admin, &addresses.third_party, &future, &100),
+            ContractError::SeriesDefaulted,
+        );
+        assert_contract_error(mint(1_000), ContractError::InvalidExpiration);
+
+        // Aggregate transfers route around the expired series
+        client.transfer(admin, &addresses.third_party, &1_000);
+        assert_eq!(client.balance_of_series(admin, &future), 400);
+        assert_eq!(client.balance_of_series(admin, &spot), 4_000);
+        assert_contract_error(
+            client.try_transfer(admin, &addresses.third_party, &4_001),
+            ContractError::InsufficientBalance,
+        );
+
+        // Declaring the default closes the series and frees its tokens again
+        client.declare_default(&addresses.guarantee_agent, &future);
+        assert!(!client.is_expired(&future));
+        client.transfer_series(admin, &addresses.third_party, &future, &400);
+    }
+
+    // Test 137: A disputed delivery stays locked until the guarantee agent resolves it
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_delivery_dispute() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let first = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let second = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        let second_buyer = Address::generate(&env);
+        for (series_id, buyer) in [(&first, &addresses.buyer), (&second, &second_buyer)] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.mint_future(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+            );
+        }
+        let agent = &addresses.guarantee_agent;
+
+        // Nothing to resolve before the buyer raises a dispute
+        assert_contract_error(
+            client.try_resolve_dispute(&first, &true, &1_000),
+            ContractError::NothingPending,
+        );
+
+        client.raise_dispute(&first, &ReasonCode::QualityDispute, &None);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert_eq!(auths[0].0, addresses.buyer);
+        assert!(has_event(&env, "dispute_raised"));
+        assert_eq!(client.series_status(&first), Some(SeriesStatus::Disputed));
+
+        // Delivery is blocked in between
+        assert_contract_error(
+            client.try_confirm_delivery(&first, &addresses.storage),
+            ExtError::SeriesDisputed,
+        );
+        let report = client.simulate(&vec![&env, SimOp::ConfirmDelivery(first.clone())]);
+        assert_eq!(report.results.get(0), Some(SimResult::SeriesDisputed));
+        assert_contract_error(
+            client.try_raise_dispute(&first, &ReasonCode::QualityDispute, &None),
+            ExtError::SeriesDisputed,
+        );
+
+        // Resolved for the buyer in part; the rest goes back to awaiting delivery
+        assert_contract_error(
+            client.try_resolve_dispute(&first, &true, &99_001),
+            ContractError::InsufficientBalance,
+        );
+        client.resolve_dispute(&first, &true, &40_000);
+        let auths = env.auths();
+        assert_eq!(auths[0].0, *agent);
+        let (_, _, data) = env.events().all().last().unwrap();
+        let resolution: (bool, Address, i128) = data.into_val(&env);
+        assert_eq!(resolution, (true, addresses.buyer.clone(), 40_000));
+        assert_eq!(client.balance_of(&addresses.buyer), 40_000);
+        assert_eq!(
+            client.series_status(&first),
+            Some(SeriesStatus::DeliveryPending)
+        );
+        client.confirm_delivery(&first, &addresses.storage);
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+        assert_eq!(client.series_status(&first), Some(SeriesStatus::Delivered));
+
+        // Resolved against the buyer: the tokens go back to the producer and the series closes
+        client.raise_dispute(&second, &ReasonCode::QualityDispute, &None);
+        let producer_held = client.balance_of(&addresses.producer);
+        client.resolve_dispute(&second, &false, &99_000);
+        assert_eq!(client.locked_balance_of(&second_buyer), 0);
+        assert_eq!(client.balance_of(&second_buyer), 0);
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            producer_held + 99_000
+        );
+        // Both escrowed fees are paid: the first at delivery, the second on resolution
+        assert_eq!(client.balance_of(agent), 500 + 500);
+        assert_eq!(client.series_status(&second), Some(SeriesStatus::Delivered));
+        assert_contract_error(
+            client.try_confirm_delivery(&second, &addresses.storage),
+            ContractError::AlreadyDelivered,
+        );
+
+        // Only the guarantee agent resolves
+        let third = String::from_str(&env, "CTGSoy-USD-2026Q2");
+        let mut metadata = create_future_metadata(&env, &addresses);
+        metadata.id = third.clone();
+        client.mint_future(
+            &addresses.admin,
+            &third,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(agent.clone()),
+            &100_000,
+        );
+        client.raise_dispute(&third, &ReasonCode::QualityDispute, &None);
+        env.mock_auths(&[]);
+        assert!(client.try_resolve_dispute(&third, &true, &1_000).is_err());
+    }
+
+    // Test 138: A future's payment is escrowed in a payment token until delivery
+    #[cfg(all(feature = "defaults", feature = "payments"))]
+    #[test]
+    fn test_payment_escrow() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let usdc = env
+            .register_stellar_asset_contract_v2(addresses.admin.clone())
+            .address();
+        StellarAssetClient::new(&env, &usdc).mint(&addresses.buyer, &1_000_000);
+        let usdc_client = TokenClient::new(&env, &usdc);
+        let agent = Some(addresses.guarantee_agent.clone());
+
+        let delivered = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let cancelled = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        let defaulted = String::from_str(&env, "CTGSoy-USD-2026Q2");
+        for series_id in [&delivered, &cancelled, &defaulted] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.open_future_with_escrow(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &agent,
+                &100_000,
+                &usdc,
+                &250_000,
+            );
+        }
+        assert!(env.auths().iter().any(|(a, _)| *a == addresses.buyer));
+        assert_eq!(usdc_client.balance(&addresses.buyer), 250_000);
+        assert_eq!(usdc_client.balance(&client.address), 750_000);
+        assert_eq!(
+            client.payment_escrow(&delivered),
+            Some(PaymentEscrow {
+                token: usdc.clone(),
+                amount: 250_000,
+            })
+        );
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 3 * 99_000);
+
+        // One escrow per series, and a price is required
+        let mut metadata = create_future_metadata(&env, &addresses);
+        metadata.id = delivered.clone();
+        assert_contract_error(
+            client.try_open_future_with_escrow(
+                &addresses.admin,
+                &delivered,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &agent,
+                &100_000,
+                &usdc,
+                &250_000,
+            ),
+            ContractError::AlreadyPending,
+        );
+        metadata.id = String::from_str(&env, "CTGSoy-USD-2026Q3");
+        assert_contract_error(
+            client.try_open_future_with_escrow(
+                &addresses.admin,
+                &metadata.id,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &agent,
+                &100_000,
+                &usdc,
+                &0,
+            ),
+            ContractError::InvalidAmount,
+        );
+
+        // Delivery pays the producer exactly once
+        client.confirm_delivery(&delivered, &addresses.storage);
+        assert!(has_event(&env, "payment_released"));
+        assert_eq!(usdc_client.balance(&addresses.producer), 250_000);
+        assert_eq!(client.payment_escrow(&delivered), None);
+        assert_contract_error(
+            client.try_confirm_delivery(&delivered, &addresses.storage),
+            ContractError::AlreadyDelivered,
+        );
+        assert_eq!(usdc_client.balance(&addresses.producer), 250_000);
+
+        // Cancellation and default refund the buyer
+        client.cancel_future(&addresses.admin, &cancelled);
+        assert_eq!(usdc_client.balance(&addresses.buyer), 500_000);
+        env.ledger()
+            .set_timestamp(1751328000 + 7 * 24 * 60 * 60 + 1);
+        client.declare_default(&addresses.admin, &defaulted);
+        assert_eq!(usdc_client.balance(&addresses.buyer), 750_000);
+        assert_eq!(usdc_client.balance(&client.address), 0);
+        assert_eq!(usdc_client.balance(&addresses.producer), 250_000);
+    }
+
+    // Test 139: Two holders exchange tokens of different series atomically
+    #[cfg(all(feature = "batch", feature = "swaps"))]
+    #[test]
+    fn test_atomic_exchange() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        mint_swap_series(&env, &client, &addresses);
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let producer = &addresses.producer;
+        let trader = &addresses.third_party;
+        client.transfer_series(producer, trader, &usd, &20_000);
+        let admin_brl = client.balance_of_series(&addresses.admin, &brl);
+        let admin_usd = client.balance_of_series(&addresses.admin, &usd);
+        let producer_brl = client.balance_of_series(producer, &brl);
+        let producer_usd = client.balance_of_series(producer, &usd);
+        let trader_usd = client.balance_of_series(trader, &usd);
+
+        let exchange_id =
+            client.atomic_exchange(producer, &brl, &10_000, trader, &usd, &5_000, &None);
+        let auths = env.auths();
+        assert!(auths.iter().any(|(a, _)| a == producer));
+        assert!(auths.iter().any(|(a, _)| a == trader));
+        let legs = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                    == Ok(Symbol::new(&env, "exchange_leg"))
+                    && u64::try_from_val(&env, &topics.get(1).unwrap()) == Ok(exchange_id)
+            })
+            .count();
+        assert_eq!(legs, 2);
+
+        // Each leg pays the 1% transfer fee in its own series
+        assert_eq!(
+            client.balance_of_series(producer, &brl),
+            producer_brl - 10_000
+        );
+        assert_eq!(client.balance_of_series(trader, &brl), 9_900);
+        assert_eq!(client.balance_of_series(trader, &usd), trader_usd - 5_000);
+        assert_eq!(
+            client.balance_of_series(producer, &usd),
+            producer_usd + 4_950
+        );
+        assert_eq!(
+            client.balance_of_series(&addresses.admin, &brl),
+            admin_brl + 100
+        );
+        assert_eq!(
+            client.balance_of_series(&addresses.admin, &usd),
+            admin_usd + 50
+        );
+
+        // The parties can't waive the fee; the legacy flag only earns a deprecation event
+        client.atomic_exchange(producer, &brl, &1_000, trader, &usd, &500, &Some(false));
+        assert!(has_event(&env, "apply_fee_deprecated"));
+        assert_eq!(client.balance_of_series(trader, &brl), 10_890);
+        assert_eq!(
+            client.balance_of_series(producer, &usd),
+            producer_usd + 5_445
+        );
+
+        // Party B short: neither leg applies
+        let producer_brl = client.balance_of_series(producer, &brl);
+        let trader_usd = client.balance_of_series(trader, &usd);
+        assert_contract_error(
+            client.try_atomic_exchange(
+                producer,
+                &brl,
+                &1_000,
+                trader,
+                &usd,
+                &(trader_usd + 1),
+                &None,
+            ),
+            ContractError::InsufficientBalance,
+        );
+        assert_eq!(client.balance_of_series(producer, &brl), producer_brl);
+        assert_eq!(client.balance_of_series(trader, &brl), 10_890);
+        assert_eq!(client.balance_of_series(trader, &usd), trader_usd);
+
+        assert_contract_error(
+            client.try_atomic_exchange(producer, &brl, &0, trader, &usd, &500, &None),
+            ContractError::InvalidAmount,
+        );
+    }
+
+    // Test 140: An approved aggregator redeems a series on a holder's behalf
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_burn_series_from() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(100);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.set_burn_fee(&addresses.admin, &100); // 1%
+
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let producer = &addresses.producer;
+        let aggregator = Address::generate(&env);
+        client.approve(producer, &aggregator, &300_000, &200);
+        let held = client.balance_of_series(producer, &series_id);
+        let supply = client.total_supply();
+
+        client.burn_series_from(&aggregator, producer, &series_id, &100_000);
+        assert_eq!(env.auths()[0].0, aggregator);
+        let (topics, data) = last_event(&env, "redeem");
+        assert_eq!(topics.len(), 4);
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(2).unwrap()),
+            Ok(producer.clone())
+        );
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(3).unwrap()),
+            Ok(aggregator.clone())
+        );
+        let burn: BurnEvent = data.into_val(&env);
+        assert_eq!(burn.burned, 99_000);
+        assert_eq!(burn.platform_fee + burn.storage_fee, 1_000);
+
+        // Same fee split as burn_series: only the net is destroyed
+        assert_eq!(
+            client.balance_of_series(producer, &series_id),
+            held - 100_000
+        );
+        assert_eq!(client.total_supply(), supply - 99_000);
+        assert_eq!(client.allowance(producer, &aggregator), 200_000);
+        assert_eq!(
+            client.burn_breakdown().get(BurnKind::Redemption),
+            Some(99_000)
+        );
+
+        // Over the allowance
+        assert_contract_error(
+            client.try_burn_series_from(&aggregator, producer, &series_id, &200_001),
+            ContractError::InsufficientAllowance,
+        );
+
+        // Past the expiration ledger
+        env.ledger().set_sequence_number(201);
+        assert_contract_error(
+            client.try_burn_series_from(&aggregator, producer, &series_id, &1),
+            ContractError::InsufficientAllowance,
+        );
+        assert_eq!(
+            client.balance_of_series(producer, &series_id),
+            held - 100_000
+        );
+    }
+
+    // Test 141: A batch transfer pays every recipient or none
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_transfer_batch() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        client.set_transfer_fee(&addresses.admin, &100); // 1%
+        let producer = &addresses.producer;
+        let held = client.balance_of(producer);
+
+        // Up to the cap, one transfer_amounts event per leg
+        let mut batch = vec![&env];
+        for _ in 0..MAX_TRANSFER_BATCH {
+            batch.push_back((Address::generate(&env), 1_000i128));
+        }
+        client.transfer_batch(producer, &batch, &None);
+        let transfers = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                    == Ok(Symbol::new(&env, "transfer_amounts"))
+            })
+            .count();
+        assert_eq!(transfers, MAX_TRANSFER_BATCH as usize);
+        for (recipient, _) in batch.iter() {
+            assert_eq!(client.balance_of(&recipient), 990);
+        }
+        let held = held - 1_000 * MAX_TRANSFER_BATCH as i128;
+        assert_eq!(client.balance_of(producer), held);
+
+        batch.push_back((Address::generate(&env), 1_000));
+        assert_contract_error(
+            client.try_transfer_batch(producer, &batch, &None),
+            ContractError::TooManyItems,
+        );
+
+        // An empty batch moves nothing
+        client.transfer_batch(producer, &vec![&env], &None);
+        assert_eq!(client.balance_of(producer), held);
+
+        // The sender can't waive the fee; the legacy flag only earns a deprecation event
+        let payee = Address::generate(&env);
+        client.transfer_batch(producer, &vec![&env, (payee.clone(), 1_000)], &Some(false));
+        assert!(has_event(&env, "apply_fee_deprecated"));
+        assert_eq!(client.balance_of(&payee), 990);
+        let held = held - 1_000;
+
+        // A failing middle entry reverts the legs before it
+        let first = Address::generate(&env);
+        let middle = Address::generate(&env);
+        let last = Address::generate(&env);
+        assert_contract_error(
+            client.try_transfer_batch(
+                producer,
+                &vec![
+                    &env,
+                    (first.clone(), 1_000),
+                    (middle.clone(), 0),
+                    (last.clone(), 1_000),
+                ],
+                &None,
+            ),
+            ContractError::InvalidAmount,
+        );
+        client.freeze_account(&middle, &ReasonCode::Sanctions, &None);
+        assert_contract_error(
+            client.try_transfer_batch(
+                producer,
+                &vec![
+                    &env,
+                    (first.clone(), 1_000),
+                    (middle.clone(), 1_000),
+                    (last.clone(), 1_000),
+                ],
+                &None,
+            ),
+            ExtError::AccountFrozen,
+        );
+        assert_contract_error(
+            client.try_transfer_batch(
+                producer,
+                &vec![&env, (first.clone(), held), (last.clone(), 1)],
+                &None,
+            ),
+            ContractError::InsufficientBalance,
+        );
+        assert_eq!(client.balance_of(&first), 0);
+        assert_eq!(client.balance_of(&last), 0);
+        assert_eq!(client.balance_of(producer), held);
+    }
+
+    // Test 142: A quarter's spot series are minted in one atomic batch
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_mint_spot_batch() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let entry = |series_id: &str, currency: &str, amount: i128| {
+            let mut metadata = create_spot_metadata(&env, &addresses.producer);
+            metadata.id = String::from_str(&env, series_id);
+            metadata.currency = String::from_str(&env, currency);
+            MintEntry {
+                series_id: metadata.id.clone(),
+                metadata,
+                distribution: create_standard_distribution(&addresses),
+                amount,
+            }
+        };
+
+        let batch = vec![
+            &env,
+            entry("CTGSoy-BRL-2025Q1", "BRL", 100_000),
+            entry("CTGSoy-USD-2025Q1", "USD", 200_000),
+        ];
+        client.mint_spot_batch(&addresses.admin, &batch);
+        let mints = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                    == Ok(Symbol::new(&env, "mint_spot"))
+            })
+            .count();
+        assert_eq!(mints, 2);
+        assert_eq!(client.total_supply(), 300_000);
+        assert_eq!(
+            client.balance_of_series(
+                &addresses.producer,
+                &String::from_str(&env, "CTGSoy-USD-2025Q1")
+            ),
+            198_000
+        );
+
+        // One entry with bad percentages: no series of the batch is created
+        let mut bad = entry("CTGSoy-BRL-2025Q2", "BRL", 100_000);
+        bad.distribution.producer_percent = 9_000;
+        let mixed = vec![
+            &env,
+            entry("CTGSoy-USD-2025Q2", "USD", 100_000),
+            bad,
+            entry("CTGSoy-EUR-2025Q2", "EUR", 100_000),
+        ];
+        assert_contract_error(
+            client.try_mint_spot_batch(&addresses.admin, &mixed),
+            ContractError::InvalidDistribution,
+        );
+        for series_id in [
+            "CTGSoy-USD-2025Q2",
+            "CTGSoy-BRL-2025Q2",
+            "CTGSoy-EUR-2025Q2",
+        ] {
+            assert!(
+                client
+                    .get_series(&String::from_str(&env, series_id))
+                    .is_none()
+            );
+        }
+        assert_eq!(client.total_supply(), 300_000);
+
+        // A series twice in one batch, and the size cap
+        let twice = vec![
+            &env,
+            entry("CTGSoy-USD-2025Q3", "USD", 100_000),
+            entry("CTGSoy-USD-2025Q3", "USD", 100_000),
+        ];
+        assert_contract_error(
+            client.try_mint_spot_batch(&addresses.admin, &twice),
+            ContractError::DuplicateItem,
+        );
+        let mut oversized = vec![&env];
+        for _ in 0..=MAX_MINT_BATCH {
+            oversized.push_back(entry("CTGSoy-USD-2025Q3", "USD", 1));
+        }
+        assert_contract_error(
+            client.try_mint_spot_batch(&addresses.admin, &oversized),
+            ContractError::TooManyItems,
+        );
+
+        // Minter role required
+        let outsider = Address::generate(&env);
+        assert_contract_error(
+            client.try_mint_spot_batch(&outsider, &batch),
+            ContractError::Unauthorized,
+        );
+    }
+
+    // Test 143: Mint and burn events carry who was credited what
+    #[test]
+    fn test_structured_mint_and_burn_events() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let admin = &addresses.admin;
+
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        let series_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        assert_eq!(
+            topics,
+            (Symbol::new(&env, "mint_spot"), series_id.clone()).into_val(&env)
+        );
+        let mint: MintEvent = data.into_val(&env);
+        assert_eq!(
+            mint,
+            MintEvent {
+                amount: 1_000_000,
+                recipients: vec![
+                    &env,
+                    (addresses.producer.clone(), 990_000),
+                    (admin.clone(), 5_000),
+                    (addresses.storage.clone(), 5_000),
+                ],
+                escrowed: 0,
+                reserve: 0,
+            }
+        );
+
+        // A future's buyer share is locked and the agent's fee escrowed
+        let future = create_future_metadata(&env, &addresses);
+        client.mint_future(
+            admin,
+            &future.id,
+            &future,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let (_, _, data) = env.events().all().last().unwrap();
+        let mint: MintEvent = data.into_val(&env);
+        assert_eq!(
+            mint.recipients,
+            vec![
+                &env,
+                (addresses.buyer.clone(), 99_000),
+                (admin.clone(), 500)
+            ]
+        );
+        assert_eq!((mint.escrowed, mint.reserve), (500, 0));
+
+        // Series redemptions break the fee down by recipient
+        client.set_burn_fee(admin, &100); // 1%
+        client.set_platform_fee(admin, &300);
+        client.set_storage_fee(admin, &100);
+        client.burn_series(&addresses.producer, &series_id, &100_000);
+        let (topics, data) = last_event(&env, "redeem");
+        assert_eq!(
+            topics,
+            (
+                Symbol::new(&env, "redeem"),
+                series_id.clone(),
+                addresses.producer.clone()
+            )
+                .into_val(&env)
+        );
+        let burn: BurnEvent = data.into_val(&env);
+        assert_eq!(
+            burn,
+            BurnEvent {
+                burned: 99_000,
+                platform_fee: 750,
+                storage_fee: 250,
+                fee_recipients: vec![&env, (admin.clone(), 750), (addresses.storage.clone(), 250),],
+            }
+        );
+
+        // Followed by the SEP-41 view: the fees move, the rest burns
+        let (topics, data) = last_event(&env, "burn");
+        assert_eq!(
+            topics,
+            (Symbol::new(&env, "burn"), addresses.producer.clone()).into_val(&env)
+        );
+        assert_eq!(i128::try_from_val(&env, &data).unwrap(), 99_000);
+        let (topics, data) = last_event(&env, "transfer");
+        assert_eq!(
+            topics,
+            (
+                Symbol::new(&env, "transfer"),
+                addresses.producer.clone(),
+                addresses.storage.clone()
+            )
+                .into_val(&env)
+        );
+        assert_eq!(i128::try_from_val(&env, &data).unwrap(), 250);
+    }
+
+    // Test 144: A mint's footprint does not grow with the number of series
+    #[test]
+    fn test_mint_cost_flat_in_series_count() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let mint = |index: u8| {
+            let id = [b'S', b'0' + index / 10, b'0' + index % 10];
+            let mut metadata = create_spot_metadata(&env, &addresses.producer);
+            metadata.id = String::from_bytes(&env, &id);
+            metadata.quantity_kg = 1_000;
+            // Fresh producers and warehouses, as in a quarter's issuance
+            let distribution = Distribution {
+                producer_address: Address::generate(&env),
+                storage_address: Address::generate(&env),
+                ..create_standard_distribution(&addresses)
+            };
+            client.mint_spot(
+                &addresses.admin,
+                &metadata.id,
+                &metadata,
+                &distribution,
+                &1_000,
+            );
+            env.cost_estimate().resources()
+        };
+
+        mint(0);
+        let early = mint(1);
+        for index in 2..40 {
+            mint(index);
+        }
+        let late = mint(40);
+        // Same entries touched; the only growth is the admin's held-series index, one id per
+        // series (the whole series map used to be rewritten, ~60KB by now)
+        assert_eq!(late.read_entries, early.read_entries);
+        assert_eq!(late.write_entries, early.write_entries);
+        assert!(late.read_bytes - early.read_bytes <= 40 * 12);
+        assert!(late.write_bytes - early.write_bytes <= 40 * 12);
+    }
+
+    // Test 145: Migration moves a legacy deployment's entries out of instance storage
+    #[cfg(all(feature = "insights", feature = "maintenance"))]
+    #[test]
+    fn test_migrate_storage() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let producer = &addresses.producer;
+        let metadata = create_spot_metadata(&env, producer);
+        let series_id = metadata.id.clone();
+
+        // Lay out what an older deployment left behind, all in the instance
+        let mut series = Map::new(&env);
+        series.set(series_id.clone(), metadata.clone());
+        let mut balances = Map::new(&env);
+        balances.set(producer.clone(), 1_000i128);
+        let holder_keys = [
+            DataKey::Balance(producer.clone()),
+            DataKey::SeriesBalance(series_id.clone(), producer.clone()),
+            DataKey::HeldSeries(producer.clone()),
+            DataKey::SeriesMetadata(series_id.clone()),
+        ];
+        env.as_contract(&client.address, || {
+            let instance = env.storage().instance();
+            instance.set(
+                &DataKey::State,
+                &TokenStateV1 {
+                    total_supply: 1_000,
+                    balances,
+                    series,
+                    locked_tokens: Map::new(&env),
+                },
+            );
+            instance.set(&holder_keys[0], &1_000i128);
+            instance.set(&holder_keys[1], &1_000i128);
+            instance.set(&holder_keys[2], &vec![&env, series_id.clone()]);
+            instance.set(&holder_keys[3], &SeriesRecord::V2(metadata.clone()));
+        });
+        assert_eq!(client.balance_of(producer), 1_000);
+        assert_eq!(client.total_supply(), 1_000);
+        assert_eq!(
+            client.get_series_ids(&0, &10),
+            vec![&env, series_id.clone()]
+        );
+
+        let too_many = vec![
+            &env,
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+            producer.clone(),
+        ];
+        assert_eq!(too_many.len(), MAX_MIGRATION_BATCH + 1);
+        assert_contract_error(
+            client.try_migrate_storage(&too_many, &0, &10),
+            ContractError::TooManyItems,
+        );
+
+        assert_eq!(
+            client.migrate_storage(&vec![&env, producer.clone()], &0, &10),
+            1
+        );
+        assert!(has_event(&env, "storage_migrated"));
+        env.as_contract(&client.address, || {
+            for key in holder_keys.iter() {
+                assert!(!env.storage().instance().has(key));
+                assert!(env.storage().persistent().has(key));
+            }
+            let index: String = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SeriesByIndex(0))
+                .unwrap();
+            assert_eq!(index, series_id);
+            let raw: Val = env.storage().instance().get(&DataKey::State).unwrap();
+            let state = TokenState::try_from_val(&env, &raw).unwrap();
+            assert_eq!(state.total_supply, 1_000);
+        });
+
+        // Nothing observable changed, and the deployment carries on as a new one would
+        assert_eq!(client.balance_of(producer), 1_000);
+        assert_eq!(client.balance_of_series(producer, &series_id), 1_000);
+        assert_eq!(client.total_supply(), 1_000);
+        assert_eq!(
+            client.get_series_ids(&0, &10),
+            vec![&env, series_id.clone()]
+        );
+        client.transfer(producer, &addresses.buyer, &400);
+        assert_eq!(client.balance_of(&addresses.buyer), 400);
+        assert_eq!(client.total_supply(), 1_000);
+    }
+
+    // Test 146: Reads and writes keep holder and series entries from being archived
+    #[cfg(feature = "maintenance")]
+    #[test]
+    fn test_ttl_extended_on_access() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let (producer, buyer) = (&addresses.producer, &addresses.buyer);
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+        let future = create_future_metadata(&env, &addresses);
+        client.mint_future(
+            &addresses.admin,
+            &future.id,
+            &future,
+            buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let spender = Address::generate(&env);
+        client.approve(producer, &spender, &1_000, &(300 * DAY_IN_LEDGERS));
+
+        let spot_id = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let keys = [
+            DataKey::Balance(producer.clone()),
+            DataKey::LockedBalance(buyer.clone()),
+            DataKey::Allowance(AllowanceDataKey {
+                from: producer.clone(),
+                spender: spender.clone(),
+            }),
+            DataKey::SeriesMetadata(spot_id.clone()),
+        ];
+        let ttl = |key: &DataKey| {
+            env.as_contract(&client.address, || env.storage().persistent().get_ttl(key))
+        };
+        let instance_ttl =
+            || env.as_contract(&client.address, || env.storage().instance().get_ttl());
+
+        // Written entries start with the default 120 days
+        let extend_to = 120 * DAY_IN_LEDGERS;
+        for key in keys.iter() {
+            assert_eq!(ttl(key), extend_to);
+        }
+        assert_eq!(instance_ttl(), extend_to);
+
+        // Under the 30-day threshold, a read extends them again
+        env.ledger().set_sequence_number(100 * DAY_IN_LEDGERS);
+        for key in keys.iter() {
+            assert_eq!(ttl(key), 20 * DAY_IN_LEDGERS);
+        }
+        assert_eq!(client.balance_of(producer), 990_000);
+        assert_eq!(client.locked_balance_of(buyer), 99_000);
+        assert_eq!(client.allowance(producer, &spender), 1_000);
+        assert!(client.get_series(&spot_id).is_some());
+        for key in keys.iter() {
+            assert_eq!(ttl(key), extend_to);
+        }
+        assert_eq!(instance_ttl(), extend_to);
+
+        // Above it, nothing changes
+        env.ledger().set_sequence_number(110 * DAY_IN_LEDGERS);
+        client.balance_of(producer);
+        assert_eq!(ttl(&keys[0]), 110 * DAY_IN_LEDGERS);
+
+        // Idle holdings and series can be kept alive by anyone
+        let storage_balance = DataKey::Balance(addresses.storage.clone());
+        let storage_series = DataKey::SeriesBalance(spot_id.clone(), addresses.storage.clone());
+        let supply = DataKey::SeriesSupply(spot_id.clone());
+        for key in [&storage_balance, &storage_series, &supply] {
+            assert_eq!(ttl(key), 10 * DAY_IN_LEDGERS);
+        }
+        client.bump_balance(&addresses.storage);
+        client.bump_series(&spot_id);
+        for key in [&storage_balance, &storage_series, &supply] {
+            assert_eq!(ttl(key), extend_to);
+        }
+        assert_contract_error(
+            client.try_bump_series(&String::from_str(&env, "CTGSoy-BRL-1999Q1")),
+            ContractError::SeriesNotFound,
+        );
+
+        // The admin sets the policy, within the network's limits
+        assert_contract_error(
+            client.try_set_ttl_policy(&(200 * DAY_IN_LEDGERS), &(100 * DAY_IN_LEDGERS)),
+            ContractError::InvalidParameter,
+        );
+        assert_contract_error(
+            client.try_set_ttl_policy(&(30 * DAY_IN_LEDGERS), &(400 * DAY_IN_LEDGERS)),
+            ContractError::InvalidParameter,
+        );
+        let change = client.set_ttl_policy(&(150 * DAY_IN_LEDGERS), &(200 * DAY_IN_LEDGERS));
+        assert_eq!(change.value, (200 * DAY_IN_LEDGERS) as i128);
+        assert!(has_event(&env, "ttl_policy_changed"));
+        let config = client.get_config().unwrap();
+        assert_eq!(config.ttl_threshold, 150 * DAY_IN_LEDGERS);
+        assert_eq!(config.ttl_extend_to, 200 * DAY_IN_LEDGERS);
+        client.balance_of(producer);
+        assert_eq!(ttl(&keys[0]), 200 * DAY_IN_LEDGERS);
+    }
+
+    // Test 147: An upgrade swaps the code under the contract's address and keeps its state
+    #[test]
+    fn test_upgrade_keeps_state() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        mint_spot_tokens(&env, &client, &addresses, 1_000_000);
+
+        // Smallest module the host takes: no code, just the env meta naming protocol 22
+        let mut wasm = [0u8; 40];
+        wasm[..8].copy_from_slice(b"\0asm\x01\0\0\0");
+        wasm[8..11].copy_from_slice(&[0, 30, 17]); // Custom section, 30 bytes, 17-byte name
+        wasm[11..28].copy_from_slice(b"contractenvmetav0");
+        wasm[35] = 22; // Interface version entry: protocol 22, no pre-release
+        let new_hash = env
+            .deployer()
+            .upload_contract_wasm(Bytes::from_slice(&env, &wasm));
+
+        assert_contract_error(client.try_upgrade(&new_hash), ExtError::NotPaused);
+        client.pause(&addresses.admin, &ReasonCode::OperationalError, &None);
+
+        // Reinstalling the code the last upgrade put in place is refused
+        let mut config = client.get_config().unwrap();
+        config.installed_wasm = Some(new_hash.clone());
+        env.as_contract(&client.address, || {
+            env.storage().instance().set(&DataKey::Config, &config)
+        });
+        assert_contract_error(
+            client.try_upgrade(&new_hash),
+            ContractError::InvalidParameter,
+        );
+        config.installed_wasm = None;
+        env.as_contract(&client.address, || {
+            env.storage().instance().set(&DataKey::Config, &config)
+        });
+
+        client.upgrade(&new_hash);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(topics, (Symbol::new(&env, "upgraded"),).into_val(&env));
+        assert_eq!(BytesN::<32>::try_from_val(&env, &data).unwrap(), new_hash);
+
+        // The new module has no functions, so read the state it inherited from inside
+        env.as_contract(&client.address, || {
+            assert_eq!(
+                ContangoToken::balance_of(env.clone(), addresses.producer.clone()),
+                990_000
+            );
+            assert_eq!(ContangoToken::total_supply(env.clone()), 1_000_000);
+            let config = ContangoToken::get_config(env.clone()).unwrap();
+            assert_eq!(config.admin, addresses.admin);
+            assert_eq!(config.installed_wasm, Some(new_hash.clone()));
+            assert!(ContangoToken::is_paused(env.clone()));
+        });
+    }
+
+    #[cfg(all(feature = "payments", feature = "producers"))]
+    mod reentrant_token {
+        use soroban_sdk::{
+            Address, Env, Error, IntoVal, String, Symbol, Vec, contract, contractimpl,
+            symbol_short, vec,
+        };
+
+        /// Payment token whose `transfer` calls back into the token contract to confirm a
+        /// delivery, recording whether each reentering call got through
+        #[contract]
+        pub struct ReentrantToken;
+
+        #[contractimpl]
+        impl ReentrantToken {
+            pub fn arm(env: Env, target: Address, series_id: String, validator: Address) {
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("target"), &(target, series_id, validator));
+            }
+
+            pub fn transfer(env: Env, _from: Address, _to: Address, _amount: i128) {
+                let (target, series_id, validator): (Address, String, Address) = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("target"))
+                    .unwrap();
+                let result = env.try_invoke_contract::<(), Error>(
+                    &target,
+                    &Symbol::new(&env, "confirm_delivery"),
+                    vec![&env, series_id.into_val(&env), validator.into_val(&env)],
+                );
+                let mut attempts = Self::attempts(env.clone());
+                attempts.push_back(matches!(result, Ok(Ok(()))));
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("attempts"), &attempts);
+            }
+
+            pub fn attempts(env: Env) -> Vec<bool> {
+                env.storage()
+                    .instance()
+                    .get(&symbol_short!("attempts"))
+                    .unwrap_or(Vec::new(&env))
+            }
+        }
+    }
+
+    // Test 148: A payment token reentering mid-transfer finds no way back in
+    #[cfg(all(feature = "payments", feature = "producers"))]
+    #[test]
+    fn test_reentrant_payment_token_rejected() {
+        use reentrant_token::{ReentrantToken, ReentrantTokenClient};
+
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let token = env.register(ReentrantToken, ());
+        let token_client = ReentrantTokenClient::new(&env, &token);
+        token_client.arm(&client.address, &series_id, &addresses.storage);
+
+        // Pulling the payment in: the future is minted and escrowed before the call out
+        client.open_future_with_escrow(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+            &token,
+            &250_000,
+        );
+        assert_eq!(token_client.attempts(), vec![&env, false]);
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::DeliveryPending)
+        );
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 99_000);
+
+        // Paying the producer out: the delivery is recorded once, escrow already cleared
+        client.confirm_delivery(&series_id, &addresses.storage);
+        assert_eq!(token_client.attempts(), vec![&env, false, false]);
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::Delivered)
+        );
+        assert_eq!(client.balance_of(&addresses.buyer), 99_000);
+        assert_eq!(client.payment_escrow(&series_id), None);
+        assert_eq!(client.producer_record(&addresses.producer).on_time, 1);
+
+        // The busy mark is gone once the flow ends, and turns guarded entry points away
+        // while it stands
+        env.as_contract(&client.address, || {
+            assert!(!env.storage().temporary().has(&ExtKey::Busy));
+            env.storage().temporary().set(&ExtKey::Busy, &true);
+        });
+        assert_contract_error(
+            client.try_transfer(&addresses.buyer, &addresses.third_party, &1_000),
+            ExtError::Busy,
+        );
+        assert_contract_error(
+            client.try_confirm_delivery(&series_id, &addresses.storage),
+            ExtError::Busy,
+        );
+        env.as_contract(&client.address, || {
+            env.storage().temporary().remove(&ExtKey::Busy)
+        });
+        client.transfer(&addresses.buyer, &addresses.third_party, &1_000);
+        assert_eq!(client.balance_of(&addresses.third_party), 1_000);
+    }
+
+    // Test 149: Series transfers honour the allowlist and need fresh backing
+    #[cfg(all(
+        feature = "allowlists",
+        feature = "batch",
+        feature = "insights",
+        feature = "producers",
+        feature = "swaps"
+    ))]
+    #[test]
+    fn test_series_transfer_allowlist_and_backing() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_swap_series(&env, &client, &addresses);
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        client.add_series_recipient(&usd, &addresses.buyer);
+
+        // A closed series turns outsiders away on every path that moves its balance
+        assert_contract_error(
+            client.try_transfer_series(&addresses.producer, &addresses.third_party, &usd, &1_000),
+            ContractError::RecipientNotAllowed,
+        );
+        assert_contract_error(
+            client.try_net_settle(
+                &addresses.producer,
+                &addresses.third_party,
+                &vec![&env, (usd.clone(), 1_000)],
+            ),
+            ContractError::RecipientNotAllowed,
+        );
+        client.transfer_series(&addresses.producer, &addresses.buyer, &usd, &1_000);
+        assert_eq!(client.balance_of_series(&addresses.buyer, &usd), 1_000);
+
+        // Stale backing holds the series still
+        let facility = String::from_str(&env, "AGRARIA");
+        client.set_facility_operator(&facility, &addresses.storage);
+        client.attest_inventory(
+            &facility,
+            &usd,
+            &1_000_000,
+            &String::from_str(&env, "0xfeedface"),
+        );
+        client.set_attestation_max_age(&(30 * 24 * 60 * 60));
+        env.ledger().set_timestamp(1741564800 + 31 * 24 * 60 * 60);
+        assert_contract_error(
+            client.try_transfer_series(&addresses.producer, &addresses.buyer, &usd, &1_000),
+            ContractError::SeriesPaused,
+        );
+    }
+
+    // Test 150: Single and batch confirmation pass the same gate
+    #[cfg(all(
+        feature = "batch",
+        feature = "insights",
+        feature = "producers",
+        feature = "series-admin"
+    ))]
+    #[test]
+    fn test_delivery_gate() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let assert_blocked = |expected: Error| {
+            assert_contract_error(
+                client.try_confirm_delivery(&series_id, &addresses.storage),
+                expected,
+            );
+            assert_contract_error(
+                client.try_confirm_delivery_batch(
+                    &addresses.storage,
+                    &vec![&env, (series_id.clone(), 1_000)],
+                ),
+                expected,
+            );
+        };
+
+        client.pause(&addresses.admin, &ReasonCode::OperationalError, &None);
+        assert_blocked(ContractError::ContractPaused.into());
+        client.unpause(&addresses.admin);
+
+        // Stale backing
+        let facility = String::from_str(&env, "SLC");
+        client.set_facility_operator(&facility, &addresses.storage);
+        client.attest_inventory(
+            &facility,
+            &series_id,
+            &100_000,
+            &String::from_str(&env, "0xfeedface"),
+        );
+        client.set_attestation_max_age(&(30 * 24 * 60 * 60));
+        env.ledger().set_timestamp(1741564800 + 31 * 24 * 60 * 60);
+        assert_blocked(ContractError::SeriesPaused.into());
+        client.attest_inventory(
+            &facility,
+            &series_id,
+            &100_000,
+            &String::from_str(&env, "0xc0ffee"),
+        );
+
+        // A frozen token recipient doesn't hold the delivery up
+        client.set_token_recipient(&series_id, &addresses.third_party);
+        client.freeze_account(&addresses.third_party, &ReasonCode::Sanctions, &None);
+        client.confirm_delivery(&series_id, &addresses.storage);
+        assert_eq!(client.balance_of(&addresses.third_party), 99_000);
+        assert_blocked(ContractError::AlreadyDelivered.into());
+    }
+
+    // Test 151: A late penalty is cut down to the supply cap instead of blocking delivery
+    #[test]
+    fn test_late_penalty_respects_supply_cap() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = ContangoTokenClient::new(&env, &env.register(ContangoToken, ()));
+        let addresses = TestAddresses {
+            admin: Address::generate(&env),
+            storage: Address::generate(&env),
+            producer: Address::generate(&env),
+            buyer: Address::generate(&env),
+            guarantee_agent: Address::generate(&env),
+            third_party: Address::generate(&env),
+        };
+        client.initialize(
+            &String::from_str(&env, "Contango Token"),
+            &String::from_str(&env, "CTG"),
+            &addresses.admin,
+            &addresses.storage,
+            &String::from_str(&env, "contango.com.br"),
+            &500_000,
+        );
+
+        mint_penalty_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        assert_eq!(client.supply_headroom(), Some(0));
+
+        client.burn(&addresses.admin, &2_000);
+        assert_eq!(client.supply_headroom(), Some(2_000));
+
+        // 10 days late accrues 1% (4,950); only 2,000 fits under the cap
+        env.ledger().set_timestamp(1751328000 + 10 * 86_400);
+        client.confirm_delivery(&series_id, &addresses.storage);
+        let capped = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                topics
+                    .get(0)
+                    .and_then(|topic| Symbol::try_from_val(&env, &topic).ok())
+                    == Some(Symbol::new(&env, "late_penalty_capped"))
+            })
+            .map(|(_, _, data)| <(i128, i128)>::try_from_val(&env, &data).unwrap());
+        assert_eq!(capped, Some((4_950, 2_000)));
+        assert_eq!(client.total_supply(), 500_000);
+        assert_eq!(client.locked_balance_of(&addresses.buyer), 0);
+        assert_eq!(client.balance_of(&addresses.buyer), 497_000);
+    }
+
+    // Test 152: A payout route redirects a future's escrowed payment at delivery
+    #[cfg(feature = "payments")]
+    #[test]
+    fn test_payout_route() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let usdc = env
+            .register_stellar_asset_contract_v2(addresses.admin.clone())
+            .address();
+        StellarAssetClient::new(&env, &usdc).mint(&addresses.buyer, &1_000_000);
+        let usdc_client = TokenClient::new(&env, &usdc);
+        let collection = Address::generate(&env);
+
+        let routed = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let cleared = String::from_str(&env, "CTGSoy-USD-2026Q1");
+        for series_id in [&routed, &cleared] {
+            let mut metadata = create_future_metadata(&env, &addresses);
+            metadata.id = series_id.clone();
+            client.open_future_with_escrow(
+                &addresses.admin,
+                series_id,
+                &metadata,
+                &addresses.buyer,
+                &None,
+                &Some(addresses.guarantee_agent.clone()),
+                &100_000,
+                &usdc,
+                &250_000,
+            );
+        }
+
+        // Producer and admin both sign, and the change is announced
+        client.set_payout_route(&routed, &collection);
+        let auths = env.auths();
+        assert!(auths.iter().any(|(a, _)| *a == addresses.producer));
+        assert!(auths.iter().any(|(a, _)| *a == addresses.admin));
+        let (_, _, data) = env.events().all().last().unwrap();
+        let (old, new): (Address, Address) = data.into_val(&env);
+        assert_eq!((old, new), (addresses.producer.clone(), collection.clone()));
+        assert_eq!(client.payout_route(&routed), Some(collection.clone()));
+
+        client.confirm_delivery(&routed, &addresses.storage);
+        assert_eq!(usdc_client.balance(&collection), 250_000);
+        assert_eq!(usdc_client.balance(&addresses.producer), 0);
+
+        // Cleared before delivery, the payment goes back to the producer
+        client.set_payout_route(&cleared, &collection);
+        client.set_payout_route(&cleared, &addresses.producer);
+        assert_eq!(client.payout_route(&cleared), None);
+        client.confirm_delivery(&cleared, &addresses.storage);
+        assert_eq!(usdc_client.balance(&addresses.producer), 250_000);
+        assert_eq!(usdc_client.balance(&collection), 250_000);
+
+        // Once delivered the route is settled
+        assert_contract_error(
+            client.try_set_payout_route(&routed, &addresses.producer),
+            ContractError::AlreadyDelivered,
+        );
+        assert_eq!(client.payout_route(&routed), Some(collection));
+    }
+
+    // Test 153: Freezes, pauses and disputes keep their reason until lifted
+    #[cfg(all(feature = "defaults", feature = "insights"))]
+    #[test]
+    fn test_reason_codes() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let codes = [
+            ReasonCode::Sanctions,
+            ReasonCode::CourtOrder,
+            ReasonCode::QualityDispute,
+            ReasonCode::OracleIncident,
+            ReasonCode::OperationalError,
+            ReasonCode::Other,
+        ];
+        let last_reason = || {
+            let (_, _, data) = env.events().all().last().unwrap();
+            Reason::try_from_val(&env, &data).unwrap()
+        };
+
+        for code in codes {
+            let reason = Reason { code, note: None };
+            client.pause(&addresses.admin, &code, &None);
+            assert_eq!(last_reason(), reason);
+            assert_eq!(client.pause_reason(), Some(reason.clone()));
+            client.unpause(&addresses.admin);
+            assert_eq!(client.pause_reason(), None);
+
+            client.freeze_account(&addresses.third_party, &code, &None);
+            assert_eq!(last_reason(), reason);
+            assert_eq!(client.freeze_reason(&addresses.third_party), Some(reason));
+            client.unfreeze_account(&addresses.third_party);
+            assert_eq!(client.freeze_reason(&addresses.third_party), None);
+        }
+
+        // Free text rides along with Other only, and within bounds
+        let note = String::from_str(&env, "Warehouse roof collapse, inspection pending");
+        client.freeze_account(
+            &addresses.third_party,
+            &ReasonCode::Other,
+            &Some(note.clone()),
+        );
+        assert_eq!(
+            client.freeze_reason(&addresses.third_party),
+            Some(Reason {
+                code: ReasonCode::Other,
+                note: Some(note.clone()),
+            })
+        );
+        assert_contract_error(
+            client.try_pause(&addresses.admin, &ReasonCode::Sanctions, &Some(note)),
+            ExtError::InvalidReason,
+        );
+        let long = String::from_bytes(&env, &[b'x'; MAX_REASON_NOTE_LEN as usize + 1]);
+        assert_contract_error(
+            client.try_pause(&addresses.admin, &ReasonCode::Other, &Some(long)),
+            ExtError::InvalidReason,
+        );
+
+        // A freeze recorded before reasons were kept still freezes, with no reason to show
+        env.as_contract(&client.address, || {
+            env.storage()
+                .instance()
+                .set(&DataKey::Frozen(addresses.buyer.clone()), &true)
+        });
+        assert!(client.is_frozen(&addresses.buyer));
+        assert_eq!(client.freeze_reason(&addresses.buyer), None);
+        client.unfreeze_account(&addresses.buyer);
+
+        // A dispute keeps its reason until resolved
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        for (released, code) in codes.into_iter().enumerate() {
+            let reason = Reason { code, note: None };
+            client.raise_dispute(&series_id, &code, &None);
+            let (_, _, data) = env.events().all().last().unwrap();
+            let (locked, emitted): (i128, Reason) = data.into_val(&env);
+            assert_eq!(
+                (locked, emitted),
+                (99_000 - released as i128, reason.clone())
+            );
+            assert_eq!(client.dispute_reason(&series_id), Some(reason));
+            client.resolve_dispute(&series_id, &true, &1);
+            assert_eq!(client.dispute_reason(&series_id), None);
+        }
+    }
+
+    // Test 154: Swaps only mint into live series and only spend from undisputed ones
+    #[cfg(all(feature = "defaults", feature = "swaps"))]
+    #[test]
+    fn test_swap_series_status() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+
+        // A future awaiting delivery can't take swapped-in tokens
+        assert_contract_error(
+            client.try_swap(&addresses.producer, &brl, &future, &1_000, &0),
+            ExtError::SeriesLocked,
+        );
+
+        // Nor can the platform's spendable share leave it while disputed
+        client.raise_dispute(&future, &ReasonCode::QualityDispute, &None);
+        assert!(client.balance_of_series(&addresses.admin, &future) > 0);
+        assert_contract_error(
+            client.try_swap(&addresses.admin, &future, &usd, &100, &0),
+            ExtError::SeriesDisputed,
+        );
+
+        // Live series still swap
+        client.swap(&addresses.producer, &brl, &usd, &1_000, &0);
+        assert_eq!(client.series_supply(&usd), 500_550);
+    }
+
+    // Test 155: Tokens of an expired future can't be swapped out
+    #[cfg(feature = "swaps")]
+    #[test]
+    fn test_swap_from_expired_series() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_swap_series(&env, &client, &addresses);
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let mut metadata = create_future_metadata(&env, &addresses);
+        metadata.currency = String::from_str(&env, "BRL");
+        metadata.delivery_date = 1741564800 + 24 * 60 * 60;
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &metadata,
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        client.swap(&addresses.admin, &future, &usd, &100, &0);
+
+        let grace = client.get_config().unwrap().default_grace_secs;
+        env.ledger()
+            .set_timestamp(metadata.delivery_date + grace + 1);
+        assert!(client.is_expired(&future));
+        assert_contract_error(
+            client.try_swap(&addresses.admin, &future, &usd, &100, &0),
+            ContractError::SeriesDefaulted,
+        );
+    }
+
+    // Test 156: Dispute rulings add up and split the escrowed payment in proportion
+    #[cfg(all(feature = "batch", feature = "defaults", feature = "payments"))]
+    #[test]
+    fn test_dispute_splits_payment_escrow() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let usdc = env
+            .register_stellar_asset_contract_v2(addresses.admin.clone())
+            .address();
+        StellarAssetClient::new(&env, &usdc).mint(&addresses.buyer, &1_000_000);
+        let usdc_client = TokenClient::new(&env, &usdc);
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.open_future_with_escrow(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+            &usdc,
+            &250_000,
+        );
+        let producer_held = client.balance_of(&addresses.producer);
+
+        // A partial delivery, then one ruling each way
+        client.confirm_delivery_batch(&addresses.storage, &vec![&env, (series_id.clone(), 9_000)]);
+        client.raise_dispute(&series_id, &ReasonCode::QualityDispute, &None);
+        client.resolve_dispute(&series_id, &true, &20_000);
+        assert_eq!(usdc_client.balance(&client.address), 250_000);
+        client.raise_dispute(&series_id, &ReasonCode::QualityDispute, &None);
+        client.resolve_dispute(&series_id, &false, &70_000);
+        assert!(has_event(&env, "payment_refunded"));
+
+        // 29,000 of 99,000 released: the producer is paid for those, the buyer refunded the rest
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::Delivered)
+        );
+        assert_eq!(
+            client.balance_of(&addresses.producer),
+            producer_held + 70_000
+        );
+        assert_eq!(usdc_client.balance(&addresses.producer), 73_233);
+        assert_eq!(usdc_client.balance(&addresses.buyer), 750_000 + 176_767);
+        assert_eq!(usdc_client.balance(&client.address), 0);
+        assert_eq!(client.payment_escrow(&series_id), None);
+    }
+
+    // Test 157: A dispute settled for the buyer completes like any other delivery
+    #[cfg(all(feature = "defaults", feature = "facility-ops", feature = "producers"))]
+    #[test]
+    fn test_dispute_completes_as_delivery() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        let custody = Address::generate(&env);
+        let operator = Address::generate(&env);
+        client.set_facility_operator(&String::from_str(&env, "SLC"), &operator);
+        client.set_delivery_fee(&20);
+        client.mint_future(
+            &addresses.admin,
+            &series_id,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &Some(custody.clone()),
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+
+        client.raise_dispute(&series_id, &ReasonCode::QualityDispute, &None);
+        client.resolve_dispute(&series_id, &true, &99_000);
+        assert!(has_event(&env, "delivery_confirmed"));
+        let (_, _, data) = env.events().all().last().unwrap();
+        let resolution: (bool, Address, i128) = data.into_val(&env);
+        assert_eq!(resolution, (true, custody.clone(), 99_000));
+
+        // The token recipient, the facility operator and the producer's record all see it
+        assert_eq!(
+            client.series_status(&series_id),
+            Some(SeriesStatus::Delivered)
+        );
+        assert_eq!(client.balance_of(&addresses.buyer), 0);
+        assert_eq!(client.balance_of(&operator), 198);
+        assert_eq!(client.balance_of(&custody), 99_000 - 198);
+        assert_eq!(client.producer_record(&addresses.producer).on_time, 1);
+    }
+
+    // Test 158: The simulator refuses what the entry points refuse
+    #[cfg(all(feature = "insights", feature = "producers", feature = "swaps"))]
+    #[test]
+    fn test_simulation_models_gates() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1741564800);
+
+        mint_swap_series(&env, &client, &addresses);
+        let brl = String::from_str(&env, "CTGSoy-BRL-2025Q1");
+        let usd = String::from_str(&env, "CTGSoy-USD-2025Q1");
+        let producer = &addresses.producer;
+        let usd_held = client.balance_of_series(producer, &usd);
+
+        // Burns count the series balance, not the pooled one
+        let burn_usd = vec![
+            &env,
+            SimOp::Burn(producer.clone(), usd.clone(), usd_held + 1),
+        ];
+        assert_eq!(
+            client.simulate(&burn_usd).results,
+            vec![&env, SimResult::InsufficientBalance]
+        );
+        assert_contract_error(
+            client.try_burn_series(producer, &usd, &(usd_held + 1)),
+            ContractError::InsufficientBalance,
+        );
+
+        // Frozen accounts and a paused contract
+        let ops = vec![
+            &env,
+            SimOp::Transfer(producer.clone(), addresses.third_party.clone(), 1_000),
+            SimOp::Burn(producer.clone(), brl.clone(), 1_000),
+        ];
+        client.freeze_account(&addresses.third_party, &ReasonCode::Sanctions, &None);
+        assert_eq!(
+            client.simulate(&ops).results,
+            vec![
+                &env,
+                SimResult::Rejected(ExtError::AccountFrozen as u32),
+                SimResult::Ok,
+            ]
+        );
+        client.unfreeze_account(&addresses.third_party);
+        client.pause(&addresses.admin, &ReasonCode::OperationalError, &None);
+        let paused = SimResult::Rejected(ContractError::ContractPaused as u32);
+        assert_eq!(
+            client.simulate(&ops).results,
+            vec![&env, paused.clone(), paused]
+        );
+        client.unpause(&addresses.admin);
+
+        // Stale backing blocks redemption and delivery alike
+        let future = String::from_str(&env, "CTGSoy-USD-2025Q4");
+        client.mint_future(
+            &addresses.admin,
+            &future,
+            &create_future_metadata(&env, &addresses),
+            &addresses.buyer,
+            &None,
+            &Some(addresses.guarantee_agent.clone()),
+            &100_000,
+        );
+        let facility = String::from_str(&env, "SLC");
+        client.set_facility_operator(&facility, &addresses.storage);
+        client.attest_inventory(
+            &facility,
+            &future,
+            &100_000,
+            &String::from_str(&env, "0xfeedface"),
+        );
+        client.set_attestation_max_age(&(30 * 24 * 60 * 60));
+        env.ledger().set_timestamp(1741564800 + 31 * 24 * 60 * 60);
+        let stale = SimResult::Rejected(ContractError::SeriesPaused as u32);
+        assert_eq!(
+            client
+                .simulate(&vec![&env, SimOp::ConfirmDelivery(future.clone())])
+                .results,
+            vec![&env, stale]
+        );
+        assert_contract_error(
+            client.try_confirm_delivery(&future, &addresses.storage),
+            ContractError::SeriesPaused,
+        );
+    }
+
+    // Test 159: Per-account and per-facility settings live in their own persistent entries
+    #[cfg(all(feature = "insights", feature = "maintenance"))]
+    #[test]
+    fn test_account_and_facility_entries_persistent() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+        let facility = String::from_str(&env, "SLC");
+        let third = &addresses.third_party;
+
+        client.freeze_account(third, &ReasonCode::Sanctions, &None);
+        client.set_fee_exempt(third, &true);
+        client.grant_role(&Role::Pauser, third);
+        client.set_facility_capacity(&facility, &1_000_000);
+        client.set_facility_validator(&facility, third, &true);
+        let keys = [
+            DataKey::Frozen(third.clone()),
+            DataKey::FeeExempt(third.clone()),
+            DataKey::Role(Role::Pauser, third.clone()),
+            DataKey::Facility(facility.clone()),
+            DataKey::FacilityNames,
+            DataKey::FacilityValidator(facility.clone(), third.clone()),
+        ];
+        let ttl = |key: &DataKey| {
+            env.as_contract(&client.address, || {
+                assert!(!env.storage().instance().has(key));
+                env.storage().persistent().get_ttl(key)
+            })
+        };
+        let extend_to = 120 * DAY_IN_LEDGERS;
+        for key in keys.iter() {
+            assert_eq!(ttl(key), extend_to);
+        }
+
+        // Anyone can keep them alive
+        env.ledger().set_sequence_number(100 * DAY_IN_LEDGERS);
+        client.bump_balance(third);
+        client.bump_facility(&facility);
+        for key in keys.iter() {
+            assert_eq!(ttl(key), extend_to);
+        }
+
+        // Entries an older deployment left in the instance still count, and migrate out
+        let legacy = Address::generate(&env);
+        let legacy_key = DataKey::Frozen(legacy.clone());
+        env.as_contract(&client.address, || {
+            env.storage().instance().set(&legacy_key, &true);
+        });
+        assert!(client.is_frozen(&legacy));
+        client.migrate_storage(&vec![&env, legacy.clone()], &0, &0);
+        assert_eq!(ttl(&legacy_key), extend_to);
+        assert!(client.is_frozen(&legacy));
+    }
+
+    // Test 160: A deadline past the end of time fails cleanly instead of wrapping
+    #[cfg(feature = "insights")]
+    #[test]
+    fn test_acceptance_deadline_overflow() {
+        let (env, client, addresses) = setup_test_env();
+        env.mock_all_auths();
+
+        client.set_acceptance_timeout(&u64::MAX);
+        mint_acceptance_future(&env, &client, &addresses);
+        let series_id = String::from_str(&env, "CTGSoy-USD-2025Q4");
+
+        env.ledger().set_timestamp(1_000);
+        client.confirm_delivery(&series_id, &addresses.storage);
+
+        assert_contract_error(
+            client.try_finalize_delivery(&series_id),
+            ContractError::ArithmeticOverflow,
+        );
+        assert!(client.pending_delivery(&series_id).is_some());
+    }
 }